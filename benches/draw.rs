@@ -0,0 +1,35 @@
+//! Rough timing of the raster renderers, run with `cargo bench`.
+use image::{DynamicImage, GrayImage, Luma};
+use sineart::{plotter::Plotter, Point, Sine};
+use std::time::{Duration, Instant};
+
+const RUNS: u32 = 5;
+
+/// Average time taken by `draw` on a fresh plotter of a 100x100 cell gradient.
+fn time(draw: impl Fn(&mut Plotter)) -> Duration {
+    let source = GrayImage::from_fn(800, 800, |x, y| Luma([((x + y) * 255 / 1598) as u8]));
+    let source = DynamicImage::ImageLuma8(source);
+    let mut total = Duration::ZERO;
+    for _ in 0..RUNS {
+        let mut plotter = Plotter::from_image(100, 100, source.clone(), 100, 255).unwrap();
+        let start = Instant::now();
+        draw(&mut plotter);
+        total += start.elapsed();
+    }
+    total / RUNS
+}
+
+fn main() {
+    // supersampling by 1 traces every cell on its own, unlike draw which reuses a template per
+    // amplitude
+    println!("draw:     {:?}", time(|p| p.draw(2)));
+    println!("per cell: {:?}", time(|p| p.draw_supersampled(2, 1)));
+    println!("triangle: {:?}", time(|p| p.draw_triangle(2)));
+
+    // marching a single large wave, dominated by evaluating its equation
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        Sine::new(Point::origin(), 2000, 2000).points();
+    }
+    println!("sine:     {:?}", start.elapsed() / RUNS);
+}
@@ -1,33 +1,119 @@
 use crate::curves::Point;
-use image::{GrayImage, Luma};
-use std::path::Path;
+use image::{
+    imageops::{self, FilterType},
+    EncodableLayout, GrayImage, ImageBuffer, ImageFormat, ImageResult, Luma, PixelWithColorType,
+    Rgb, RgbImage,
+};
+use std::{
+    cmp::{max, min},
+    fs::File,
+    io::{BufWriter, Cursor, Write},
+    path::Path,
+};
 
 /// Anything that can be drawn on, in cartesian X, Y coordinates.
 pub trait XYDrawable {
-    /// Set cartesian (X, Y) coordinates: X == J and Y == -I.
-    fn set_xy(&mut self, x: u32, y: u32, value: u8);
+    /// Value stored in each pixel, e.g. a gray level or an RGB color.
+    type Value: Copy;
+
+    /// Value used when drawing strokes onto the drawable.
+    fn ink(&self) -> Self::Value;
+
+    /// Get the value at cartesian (X, Y) coordinates, or `None` if they fall outside the image.
+    fn try_get_xy(&self, x: i32, y: i32) -> Option<Self::Value>;
+
+    /// Set cartesian (X, Y) coordinates: X == J and Y == -I. Returns whether the coordinates fell
+    /// inside the image, otherwise nothing is written. Negative coordinates are always outside.
+    fn try_set_xy(&mut self, x: i32, y: i32, value: Self::Value) -> bool;
+
+    /// Get the value at cartesian (X, Y) coordinates, the counterpart of [`XYDrawable::set_xy`].
+    /// Panics if the coordinates fall outside the image.
+    fn get_xy(&self, x: i32, y: i32) -> Self::Value {
+        self.try_get_xy(x, y)
+            .expect("coordinates outside of drawable")
+    }
+
+    /// Set cartesian (X, Y) coordinates, silently ignoring any that fall outside the image.
+    fn set_xy(&mut self, x: i32, y: i32, value: Self::Value) {
+        self.try_set_xy(x, y, value);
+    }
 
     /// Set a point in cartesian coordinates.
-    fn set_point(&mut self, point: &Point, value: u8) {
+    fn set_point(&mut self, point: &Point, value: Self::Value) {
         self.set_xy(point.x, point.y, value);
     }
 
+    /// Same as [`XYDrawable::set_xy`], only ever darkening the pixel: it keeps the smallest of its
+    /// current value and `value`, so overlapping strokes do not lighten each other.
+    fn set_xy_min(&mut self, x: i32, y: i32, value: Self::Value)
+    where
+        Self::Value: Ord,
+    {
+        if let Some(current) = self.try_get_xy(x, y) {
+            self.set_xy(x, y, current.min(value));
+        }
+    }
+
     /// Since the sine waves travel along the horizontal axis, we want to plot their thickness
     /// horizontally. This ensures that for white pixels (i.e. A=0 => flat lines) there is no
     /// increase in visibility, whereas for dark pixels (high amplitude), the sine wave has a
     /// steeper slope and thus a thicker line.
-    fn set_horizontal_line(&mut self, point: &Point, value: u8, extent: u32) {
-        for x in point.x.saturating_sub(extent)..=point.x + extent {
+    fn set_horizontal_line(&mut self, point: &Point, value: Self::Value, extent: u32) {
+        for x in point.x.saturating_sub_unsigned(extent)..=point.x.saturating_add_unsigned(extent) {
             self.set_xy(x, point.y, value)
         }
     }
 
-    /// Save the drawable to disk as an image.
-    fn save<P: AsRef<Path>>(&self, path: P);
+    /// Vertical counterpart of [`XYDrawable::set_horizontal_line`], setting `extent` pixels above
+    /// and below the point.
+    fn set_vertical_line(&mut self, point: &Point, value: Self::Value, extent: u32) {
+        for y in point.y.saturating_sub_unsigned(extent)..=point.y.saturating_add_unsigned(extent) {
+            self.set_xy(point.x, y, value)
+        }
+    }
+
+    /// Encode the drawable as an image in the given format, e.g. into an in-memory buffer.
+    fn save_to_writer<W: Write>(&self, writer: &mut W, format: ImageFormat) -> ImageResult<()>;
+
+    /// Save the drawable to disk as an image, in the format given by the path's extension.
+    fn save<P: AsRef<Path>>(&self, path: P) -> ImageResult<()> {
+        let format = ImageFormat::from_path(&path)?;
+        let mut file = BufWriter::new(File::create(path)?);
+        self.save_to_writer(&mut file, format)?;
+        file.flush()?;
+        Ok(())
+    }
 }
 
-/// Canvas with an inner image that is drawable, and a white border that will remain blank.
-#[derive(Debug, Clone)]
+/// Encode an image into any writer. The encoders may need to seek, so the image is encoded in
+/// memory first.
+pub(crate) fn write_image<P, W>(
+    image: &ImageBuffer<P, Vec<P::Subpixel>>,
+    writer: &mut W,
+    format: ImageFormat,
+) -> ImageResult<()>
+where
+    P: PixelWithColorType,
+    [P::Subpixel]: EncodableLayout,
+    W: Write,
+{
+    let mut buffer = Cursor::new(Vec::new());
+    image.write_to(&mut buffer, format)?;
+    writer.write_all(buffer.get_ref())?;
+    Ok(())
+}
+
+/// Convert cartesian (X, Y) coordinates to image (J, I) ones, for an image of full size
+/// `fw` x `fh` whose inner part is offset by `ow` and `oh`. Returns `None` if outside the image.
+fn image_coords(fw: u32, fh: u32, ow: u32, oh: u32, x: i32, y: i32) -> Option<(u32, u32)> {
+    let j = u32::try_from(x).ok()?.checked_add(ow).filter(|&j| j < fw)?;
+    let i = (fh - 1 - oh).checked_sub(u32::try_from(y).ok()?)?;
+    Some((j, i))
+}
+
+/// Canvas with an inner image that is drawable, and a border that will remain blank. Both are
+/// white with black strokes unless told otherwise.
+#[derive(Debug, Clone, PartialEq)]
 pub struct Canvas {
     /// Full width of image, in pixels.
     pub fw: u32,
@@ -41,12 +127,23 @@ pub struct Canvas {
     pub ow: u32,
     /// Plotting offset height, when asking to set P(x, y), this must be in the inner image.
     pub oh: u32,
+    /// Gray level used for strokes.
+    pub ink: u8,
+    /// Gray level restored by [`Canvas::clear`].
+    background: u8,
     /// Image buffer.
     image: GrayImage,
 }
 
 impl Canvas {
     pub fn new(full_hw: [u32; 2], inner_hw: [u32; 2]) -> Self {
+        Self::with_background(full_hw, inner_hw, 255)
+    }
+
+    /// Canvas filled with the given gray level. Strokes are drawn in whichever of black or white
+    /// contrasts most with the background, so they never end up invisible, e.g. a black
+    /// background gets white strokes.
+    pub fn with_background(full_hw: [u32; 2], inner_hw: [u32; 2], value: u8) -> Self {
         Self {
             fh: full_hw[0],
             fw: full_hw[1],
@@ -54,18 +151,832 @@ impl Canvas {
             iw: inner_hw[1],
             oh: (full_hw[0] - inner_hw[0]) / 2,
             ow: (full_hw[1] - inner_hw[1]) / 2,
-            image: GrayImage::from_pixel(full_hw[1], full_hw[0], Luma([255])),
+            ink: if value < 128 { 255 } else { 0 },
+            background: value,
+            image: GrayImage::from_pixel(full_hw[1], full_hw[0], Luma([value])),
+        }
+    }
+
+    /// Canvas drawing over an existing image, e.g. a photo for a collage, its inner part of size
+    /// `inner_hw` starting `offset` (height, width) pixels from the bottom left corner. Returns
+    /// `None` if the inner part and an equal border on the far side do not fit in the image.
+    /// Strokes are black, and [`Canvas::clear`] wipes the image to white.
+    pub fn from_image(image: GrayImage, inner_hw: [u32; 2], offset: [u32; 2]) -> Option<Self> {
+        let fits = |inner: u32, offset: u32, full: u32| {
+            offset
+                .checked_mul(2)
+                .and_then(|border| border.checked_add(inner))
+                .is_some_and(|needed| needed <= full)
+        };
+        if !fits(inner_hw[0], offset[0], image.height())
+            || !fits(inner_hw[1], offset[1], image.width())
+        {
+            return None;
+        }
+        Some(Self {
+            fh: image.height(),
+            fw: image.width(),
+            ih: inner_hw[0],
+            iw: inner_hw[1],
+            oh: offset[0],
+            ow: offset[1],
+            ink: 0,
+            background: 255,
+            image,
+        })
+    }
+
+    /// Underlying image, border included.
+    pub fn image(&self) -> &GrayImage {
+        &self.image
+    }
+
+    /// Mutable access to the underlying image, for drawing without borrowing the canvas.
+    pub(crate) fn image_mut(&mut self) -> &mut GrayImage {
+        &mut self.image
+    }
+
+    /// Gray level restored by [`Canvas::clear`].
+    pub fn background(&self) -> u8 {
+        self.background
+    }
+
+    /// Wipe everything drawn, border included, back to the background.
+    pub fn clear(&mut self) {
+        self.fill(self.background);
+    }
+
+    /// Fill the whole canvas with a new background. The ink is left as is.
+    pub fn fill(&mut self, value: u8) {
+        self.background = value;
+        for pixel in self.image.pixels_mut() {
+            *pixel = Luma([value]);
+        }
+    }
+
+    /// Copy of the canvas with every dimension multiplied by `factor`, each pixel becoming a
+    /// `factor` x `factor` block.
+    pub fn upscaled(&self, factor: u32) -> Self {
+        Self {
+            fw: self.fw * factor,
+            fh: self.fh * factor,
+            iw: self.iw * factor,
+            ih: self.ih * factor,
+            ow: self.ow * factor,
+            oh: self.oh * factor,
+            ink: self.ink,
+            background: self.background,
+            image: imageops::resize(
+                &self.image,
+                self.fw * factor,
+                self.fh * factor,
+                FilterType::Nearest,
+            ),
+        }
+    }
+
+    /// Preview of the inner image as text, `cols` Unicode braille characters wide, e.g. for a
+    /// terminal. Every character is a block of 2 x 4 dots, each of which is set if any pixel it
+    /// covers is darker than mid gray, so that thin strokes are not lost. Lines end in `\n`.
+    pub fn to_braille(&self, cols: usize) -> String {
+        if cols == 0 || self.iw == 0 || self.ih == 0 {
+            return String::new();
+        }
+        let (iw, ih) = (self.iw as usize, self.ih as usize);
+        let (dots_w, dots_h) = (2 * cols, 4 * max(ih * 2 * cols / iw, 4).div_ceil(4));
+        let top = self.fh - self.oh - self.ih;
+        // whether any pixel of the inner image covered by a dot is dark
+        let dot = |dx: usize, dy: usize| {
+            let (j0, j1) = (
+                dx * iw / dots_w,
+                max((dx + 1) * iw / dots_w, dx * iw / dots_w + 1),
+            );
+            let (i0, i1) = (
+                dy * ih / dots_h,
+                max((dy + 1) * ih / dots_h, dy * ih / dots_h + 1),
+            );
+            (i0..min(i1, ih)).any(|i| {
+                (j0..min(j1, iw)).any(|j| {
+                    let pixel = self.image.get_pixel(self.ow + j as u32, top + i as u32);
+                    pixel.0[0] < 128
+                })
+            })
+        };
+        // bit of each dot in a braille character, by column then row
+        const BITS: [[u32; 4]; 2] = [[0x01, 0x02, 0x04, 0x40], [0x08, 0x10, 0x20, 0x80]];
+
+        let mut text = String::new();
+        for row in 0..dots_h / 4 {
+            for col in 0..cols {
+                let mut code = 0x2800;
+                for (c, bits) in BITS.iter().enumerate() {
+                    for (r, bit) in bits.iter().enumerate() {
+                        if dot(2 * col + c, 4 * row + r) {
+                            code |= bit;
+                        }
+                    }
+                }
+                text.push(char::from_u32(code).expect("braille patterns are valid characters"));
+            }
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Replace the image with that of a larger canvas, e.g. one from [`Canvas::upscaled`],
+    /// averaging it down to this canvas' size.
+    pub fn downscale_from(&mut self, other: &Canvas) {
+        self.image = imageops::resize(&other.image, self.fw, self.fh, FilterType::Triangle);
+    }
+
+    /// Guide lines over the inner image in the given gray level, along the left and bottom
+    /// edges and every `cell_w` pixels across and `cell_h` up from them, e.g. to check how cells
+    /// line up with what is drawn. The border is left as is.
+    pub fn draw_grid(&mut self, cell_w: u32, cell_h: u32, value: u8) {
+        let (iw, ih) = (self.iw as i32, self.ih as i32);
+        if iw == 0 || ih == 0 {
+            return;
+        }
+        // lines are drawn about their middle pixel, so even lengths need one more pixel
+        for x in (0..iw).step_by(max(cell_w, 1) as usize) {
+            self.set_vertical_line(&Point::new(x, (ih - 1) / 2), value, (ih as u32 - 1) / 2);
+            self.set_xy(x, ih - 1, value);
+        }
+        for y in (0..ih).step_by(max(cell_h, 1) as usize) {
+            self.set_horizontal_line(&Point::new((iw - 1) / 2, y), value, (iw as u32 - 1) / 2);
+            self.set_xy(iw - 1, y, value);
+        }
+    }
+
+    /// Solid frame in the given gray level around the inner image, `width` pixels wide, drawn in
+    /// the border and cut short wherever the border is narrower. The inner image is left as is.
+    pub fn draw_frame(&mut self, width: u32, value: u8) {
+        let top = self.fh - self.oh - self.ih;
+        let (left, right) = (self.ow as i64, (self.ow + self.iw) as i64);
+        let (top, bottom) = (top as i64, (top + self.ih) as i64);
+        let width = width as i64;
+        for (j, i, pixel) in self.image.enumerate_pixels_mut() {
+            let (j, i) = (j as i64, i as i64);
+            let inside = (left..right).contains(&j) && (top..bottom).contains(&i);
+            let framed = (left - width..right + width).contains(&j)
+                && (top - width..bottom + width).contains(&i);
+            if framed && !inside {
+                *pixel = Luma([value]);
+            }
         }
     }
+
+    /// Copy of the image, border included, mirrored left to right. When the border is not
+    /// equally wide on both sides, the inner image moves by a pixel, see
+    /// [`Canvas::flip_inner_horizontal`] to keep it in place.
+    pub fn flip_horizontal(&self) -> GrayImage {
+        imageops::flip_horizontal(&self.image)
+    }
+
+    /// Copy of the image, border included, mirrored top to bottom, see
+    /// [`Canvas::flip_horizontal`].
+    pub fn flip_vertical(&self) -> GrayImage {
+        imageops::flip_vertical(&self.image)
+    }
+
+    /// Copy of the image with only the inner image mirrored left to right, the border staying
+    /// exactly as it is.
+    pub fn flip_inner_horizontal(&self) -> GrayImage {
+        self.map_inner(imageops::flip_horizontal)
+    }
+
+    /// Copy of the image with only the inner image mirrored top to bottom, see
+    /// [`Canvas::flip_inner_horizontal`].
+    pub fn flip_inner_vertical(&self) -> GrayImage {
+        self.map_inner(imageops::flip_vertical)
+    }
+
+    /// Copy of the image with the inner image replaced by `f` of itself, of the same size.
+    fn map_inner(&self, f: impl Fn(&GrayImage) -> GrayImage) -> GrayImage {
+        let top = self.fh - self.oh - self.ih;
+        let inner = imageops::crop_imm(&self.image, self.ow, top, self.iw, self.ih).to_image();
+        let mut image = self.image.clone();
+        imageops::replace(&mut image, &f(&inner), self.ow as i64, top as i64);
+        image
+    }
+
+    /// Copy of the image, border included, rotated `degrees` counter-clockwise about its center
+    /// as in the cartesian coordinates strokes are drawn in. Multiples of 90 are exact, any other
+    /// angle is interpolated bilinearly into the bounding box of the rotated image, the exposed
+    /// corners being filled with the background.
+    pub fn rotate(&self, degrees: f64) -> GrayImage {
+        let degrees = degrees.rem_euclid(360.0);
+        let quarter_turns = degrees / 90.0;
+        if (quarter_turns - quarter_turns.round()).abs() < 1e-9 {
+            // imageops rotates clockwise, with the image Y axis pointing down
+            return match quarter_turns.round() as u32 % 4 {
+                0 => self.image.clone(),
+                1 => imageops::rotate270(&self.image),
+                2 => imageops::rotate180(&self.image),
+                _ => imageops::rotate90(&self.image),
+            };
+        }
+        self.rotate_bilinear(degrees)
+    }
+
+    /// Arbitrary angle case of [`Canvas::rotate`].
+    fn rotate_bilinear(&self, degrees: f64) -> GrayImage {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        let (w, h) = (self.fw as f64, self.fh as f64);
+        let rw = (w * cos.abs() + h * sin.abs()).round() as u32;
+        let rh = (w * sin.abs() + h * cos.abs()).round() as u32;
+        let background = self.background as f64;
+        // pixel of the source image, or the background outside of it
+        let source = |j: i64, i: i64| match (u32::try_from(j), u32::try_from(i)) {
+            (Ok(j), Ok(i)) if j < self.fw && i < self.fh => self.image.get_pixel(j, i).0[0] as f64,
+            _ => background,
+        };
+
+        GrayImage::from_fn(rw, rh, |j, i| {
+            // offset from the center of the rotated image, Y pointing up, rotated back
+            let dx = j as f64 + 0.5 - rw as f64 / 2.0;
+            let dy = rh as f64 / 2.0 - (i as f64 + 0.5);
+            let (sx, sy) = (dx * cos + dy * sin, dy * cos - dx * sin);
+            let (sj, si) = (w / 2.0 + sx - 0.5, h / 2.0 - sy - 0.5);
+            let (j0, i0) = (sj.floor(), si.floor());
+            let (fj, fi) = (sj - j0, si - i0);
+            let (j0, i0) = (j0 as i64, i0 as i64);
+            let top = source(j0, i0) * (1.0 - fj) + source(j0 + 1, i0) * fj;
+            let bottom = source(j0, i0 + 1) * (1.0 - fj) + source(j0 + 1, i0 + 1) * fj;
+            Luma([(top * (1.0 - fi) + bottom * fi).round() as u8])
+        })
+    }
 }
 
 impl XYDrawable for Canvas {
-    fn set_xy(&mut self, x: u32, y: u32, value: u8) {
-        self.image
-            .put_pixel(x + self.ow, self.fh - 1 - y - self.oh, Luma([value]));
+    type Value = u8;
+
+    fn ink(&self) -> u8 {
+        self.ink
+    }
+
+    fn try_get_xy(&self, x: i32, y: i32) -> Option<u8> {
+        let (j, i) = image_coords(self.fw, self.fh, self.ow, self.oh, x, y)?;
+        Some(self.image.get_pixel(j, i).0[0])
+    }
+
+    fn try_set_xy(&mut self, x: i32, y: i32, value: u8) -> bool {
+        match image_coords(self.fw, self.fh, self.ow, self.oh, x, y) {
+            Some((j, i)) => {
+                self.image.put_pixel(j, i, Luma([value]));
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn save_to_writer<W: Write>(&self, writer: &mut W, format: ImageFormat) -> ImageResult<()> {
+        write_image(&self.image, writer, format)
+    }
+}
+
+/// 16-bit counterpart of [`Canvas`], for a finer range of gray levels. White with black strokes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Canvas16 {
+    /// Full width of image, in pixels.
+    pub fw: u32,
+    /// Full height of image, in pixels.
+    pub fh: u32,
+    /// Inner width of image, in pixels.
+    pub iw: u32,
+    /// Inner height of image, in pixels.
+    pub ih: u32,
+    /// Plotting offset width, when asking to set P(x, y), this must be in the inner image.
+    pub ow: u32,
+    /// Plotting offset height, when asking to set P(x, y), this must be in the inner image.
+    pub oh: u32,
+    /// Gray level used for strokes.
+    pub ink: u16,
+    /// Image buffer.
+    image: ImageBuffer<Luma<u16>, Vec<u16>>,
+}
+
+impl Canvas16 {
+    pub fn new(full_hw: [u32; 2], inner_hw: [u32; 2]) -> Self {
+        Self {
+            fh: full_hw[0],
+            fw: full_hw[1],
+            ih: inner_hw[0],
+            iw: inner_hw[1],
+            oh: (full_hw[0] - inner_hw[0]) / 2,
+            ow: (full_hw[1] - inner_hw[1]) / 2,
+            ink: 0,
+            image: ImageBuffer::from_pixel(full_hw[1], full_hw[0], Luma([u16::MAX])),
+        }
+    }
+
+    /// Underlying image, border included.
+    pub fn image(&self) -> &ImageBuffer<Luma<u16>, Vec<u16>> {
+        &self.image
+    }
+}
+
+impl XYDrawable for Canvas16 {
+    type Value = u16;
+
+    fn ink(&self) -> u16 {
+        self.ink
+    }
+
+    fn try_get_xy(&self, x: i32, y: i32) -> Option<u16> {
+        let (j, i) = image_coords(self.fw, self.fh, self.ow, self.oh, x, y)?;
+        Some(self.image.get_pixel(j, i).0[0])
+    }
+
+    fn try_set_xy(&mut self, x: i32, y: i32, value: u16) -> bool {
+        match image_coords(self.fw, self.fh, self.ow, self.oh, x, y) {
+            Some((j, i)) => {
+                self.image.put_pixel(j, i, Luma([value]));
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn save_to_writer<W: Write>(&self, writer: &mut W, format: ImageFormat) -> ImageResult<()> {
+        write_image(&self.image, writer, format)
+    }
+}
+
+/// Color counterpart of [`Canvas`], with a white border and black strokes unless told otherwise.
+#[derive(Debug, Clone)]
+pub struct RgbCanvas {
+    /// Full width of image, in pixels.
+    pub fw: u32,
+    /// Full height of image, in pixels.
+    pub fh: u32,
+    /// Inner width of image, in pixels.
+    pub iw: u32,
+    /// Inner height of image, in pixels.
+    pub ih: u32,
+    /// Plotting offset width, when asking to set P(x, y), this must be in the inner image.
+    pub ow: u32,
+    /// Plotting offset height, when asking to set P(x, y), this must be in the inner image.
+    pub oh: u32,
+    /// Color used for strokes.
+    pub ink: Rgb<u8>,
+    /// Image buffer.
+    image: RgbImage,
+}
+
+impl RgbCanvas {
+    pub fn new(full_hw: [u32; 2], inner_hw: [u32; 2], background: Rgb<u8>) -> Self {
+        Self {
+            fh: full_hw[0],
+            fw: full_hw[1],
+            ih: inner_hw[0],
+            iw: inner_hw[1],
+            oh: (full_hw[0] - inner_hw[0]) / 2,
+            ow: (full_hw[1] - inner_hw[1]) / 2,
+            ink: Rgb([0, 0, 0]),
+            image: RgbImage::from_pixel(full_hw[1], full_hw[0], background),
+        }
+    }
+}
+
+impl XYDrawable for RgbCanvas {
+    type Value = Rgb<u8>;
+
+    fn ink(&self) -> Rgb<u8> {
+        self.ink
+    }
+
+    fn try_get_xy(&self, x: i32, y: i32) -> Option<Rgb<u8>> {
+        let (j, i) = image_coords(self.fw, self.fh, self.ow, self.oh, x, y)?;
+        Some(*self.image.get_pixel(j, i))
+    }
+
+    fn try_set_xy(&mut self, x: i32, y: i32, value: Rgb<u8>) -> bool {
+        match image_coords(self.fw, self.fh, self.ow, self.oh, x, y) {
+            Some((j, i)) => {
+                self.image.put_pixel(j, i, value);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn save_to_writer<W: Write>(&self, writer: &mut W, format: ImageFormat) -> ImageResult<()> {
+        write_image(&self.image, writer, format)
+    }
+}
+
+/// Drawable with the X and Y axes swapped, so that anything travelling along +X on it travels
+/// along +Y on the underlying drawable.
+pub struct Transposed<'a, D: XYDrawable>(pub &'a mut D);
+
+impl<'a, D: XYDrawable> XYDrawable for Transposed<'a, D> {
+    type Value = D::Value;
+
+    fn ink(&self) -> D::Value {
+        self.0.ink()
+    }
+
+    fn try_get_xy(&self, x: i32, y: i32) -> Option<D::Value> {
+        self.0.try_get_xy(y, x)
+    }
+
+    fn try_set_xy(&mut self, x: i32, y: i32, value: D::Value) -> bool {
+        self.0.try_set_xy(y, x, value)
+    }
+
+    fn save_to_writer<W: Write>(&self, writer: &mut W, format: ImageFormat) -> ImageResult<()> {
+        self.0.save_to_writer(writer, format)
+    }
+}
+
+/// Drawable in "darken" blend mode: every write goes through [`XYDrawable::set_xy_min`], so
+/// anything drawn on it can only darken the underlying drawable, e.g. where strokes cross.
+pub struct Darken<'a, D: XYDrawable>(pub &'a mut D)
+where
+    D::Value: Ord;
+
+impl<'a, D: XYDrawable> XYDrawable for Darken<'a, D>
+where
+    D::Value: Ord,
+{
+    type Value = D::Value;
+
+    fn ink(&self) -> D::Value {
+        self.0.ink()
+    }
+
+    fn try_get_xy(&self, x: i32, y: i32) -> Option<D::Value> {
+        self.0.try_get_xy(x, y)
+    }
+
+    fn try_set_xy(&mut self, x: i32, y: i32, value: D::Value) -> bool {
+        match self.0.try_get_xy(x, y) {
+            Some(current) => self.0.try_set_xy(x, y, current.min(value)),
+            None => false,
+        }
+    }
+
+    fn save_to_writer<W: Write>(&self, writer: &mut W, format: ImageFormat) -> ImageResult<()> {
+        self.0.save_to_writer(writer, format)
+    }
+}
+
+/// Drawable moved by an offset: anything drawn at (X, Y) on it lands at (X, Y) + offset on the
+/// underlying drawable, e.g. to place several drawings side by side.
+pub struct Translated<'a, D: XYDrawable>(pub &'a mut D, pub Point);
+
+impl<'a, D: XYDrawable> XYDrawable for Translated<'a, D> {
+    type Value = D::Value;
+
+    fn ink(&self) -> D::Value {
+        self.0.ink()
+    }
+
+    fn try_get_xy(&self, x: i32, y: i32) -> Option<D::Value> {
+        self.0.try_get_xy(x + self.1.x, y + self.1.y)
+    }
+
+    fn try_set_xy(&mut self, x: i32, y: i32, value: D::Value) -> bool {
+        self.0.try_set_xy(x + self.1.x, y + self.1.y, value)
+    }
+
+    fn save_to_writer<W: Write>(&self, writer: &mut W, format: ImageFormat) -> ImageResult<()> {
+        self.0.save_to_writer(writer, format)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curves::{lines::AngledLine, sine::Sine, Drawable};
+
+    #[test]
+    fn clear() {
+        let mut canvas = Canvas::with_background([60; 2], [50; 2], 200);
+        Sine::new(Point::new(0, 25), 20, 10).draw_thick(&mut canvas, 2);
+        assert!(canvas.image().pixels().any(|p| p.0[0] != 200));
+        canvas.clear();
+        assert!(canvas.image().pixels().all(|p| p.0[0] == 200));
+
+        canvas.fill(30);
+        canvas.set_xy(3, 4, 0);
+        canvas.clear();
+        assert!(canvas.image().pixels().all(|p| p.0[0] == 30));
+    }
+
+    #[test]
+    fn from_image() {
+        let background = GrayImage::from_fn(60, 40, |j, i| Luma([(2 * j + i) as u8 + 50]));
+        assert!(Canvas::from_image(background.clone(), [30, 50], [6, 5]).is_none());
+        assert!(Canvas::from_image(background.clone(), [30, 51], [5, 5]).is_none());
+
+        let mut canvas = Canvas::from_image(background.clone(), [30, 50], [5, 5]).unwrap();
+        assert_eq!(
+            [canvas.fh, canvas.fw, canvas.ih, canvas.iw],
+            [40, 60, 30, 50]
+        );
+        AngledLine::new(Point::new(0, 10), Point::new(49, 10)).draw(&mut canvas);
+
+        // the line lands 5 pixels in from the bottom left, everything else is the original
+        for (j, i, pixel) in canvas.image().enumerate_pixels() {
+            if i == 40 - 1 - 5 - 10 && (5..55).contains(&j) {
+                assert_eq!(pixel.0[0], 0);
+            } else {
+                assert_eq!(pixel, background.get_pixel(j, i));
+            }
+        }
+    }
+
+    #[test]
+    fn draw_grid() {
+        for inner in [[20, 30], [21, 31]] {
+            let mut canvas = Canvas::new([inner[0] + 4, inner[1] + 4], inner);
+            canvas.draw_grid(10, 5, 0);
+            for (j, i, pixel) in canvas.image().enumerate_pixels() {
+                let (x, y) = (j as i32 - 2, (canvas.fh - 1 - i) as i32 - 2);
+                let inside = (0..inner[1] as i32).contains(&x) && (0..inner[0] as i32).contains(&y);
+                let on_grid = inside && (x % 10 == 0 || y % 5 == 0);
+                assert_eq!(pixel.0[0] == 0, on_grid, "{} {}", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn draw_frame() {
+        let mut canvas = Canvas::new([20, 30], [10, 20]);
+        canvas.draw_frame(2, 0);
+        let framed = canvas
+            .image()
+            .enumerate_pixels()
+            .filter(|(_, _, p)| p.0[0] == 0);
+        assert_eq!(framed.count(), 24 * 14 - 20 * 10);
+        // cartesian coordinates, which may fall in the border on the left and bottom
+        let at = |x: i32, y: i32| canvas.image().get_pixel((5 + x) as u32, (14 - y) as u32).0[0];
+        for (x, y) in [(-1, -1), (-2, 5), (20, 9), (21, 11), (7, 10)] {
+            assert_eq!(at(x, y), 0, "{} {}", x, y);
+        }
+        assert_eq!(at(22, 5), 255);
+        assert_eq!(at(-3, 5), 255);
+        assert!((0..20).all(|x| (0..10).all(|y| canvas.get_xy(x, y) == 255)));
+
+        // no wider than the border
+        canvas.draw_frame(100, 0);
+        let framed = canvas.image().pixels().filter(|p| p.0[0] == 0);
+        assert_eq!(framed.count(), 20 * 30 - 20 * 10);
+    }
+
+    #[test]
+    fn flip() {
+        // border one pixel wider on the right and top
+        let mut canvas = Canvas::new([13, 23], [10, 20]);
+        canvas.set_xy(0, 3, 0);
+        canvas.set_xy(5, 0, 0);
+        let at = |image: &GrayImage, x: u32, y: u32| {
+            image
+                .get_pixel(canvas.ow + x, canvas.fh - 1 - canvas.oh - y)
+                .0[0]
+        };
+
+        let inner = canvas.flip_inner_horizontal();
+        assert_eq!(at(&inner, 19, 3), 0);
+        assert_eq!(at(&inner, 0, 3), 255);
+        let inner = canvas.flip_inner_vertical();
+        assert_eq!(at(&inner, 5, 9), 0);
+        assert_eq!(at(&inner, 5, 0), 255);
+
+        // the whole image flips about its own center, so the uneven border moves the inner image
+        let whole = canvas.flip_horizontal();
+        assert_eq!(
+            whole
+                .get_pixel(23 - 1 - canvas.ow, 13 - 1 - canvas.oh - 3)
+                .0[0],
+            0
+        );
+        assert_eq!(at(&whole, 20, 3), 0);
+        let whole = canvas.flip_vertical();
+        assert_eq!(whole.get_pixel(canvas.ow + 5, canvas.oh).0[0], 0);
+        assert_eq!(canvas.flip_horizontal().dimensions(), (23, 13));
+    }
+
+    #[test]
+    fn rotate() {
+        let mut canvas = Canvas::with_background([40, 60], [30, 50], 220);
+        AngledLine::new(Point::new(5, 5), Point::new(40, 20)).draw(&mut canvas);
+
+        let quarter = canvas.rotate(90.0);
+        assert_eq!(quarter.dimensions(), (40, 60));
+        // counter-clockwise: the bottom left corner ends up bottom right
+        assert_eq!(quarter.get_pixel(39, 59), canvas.image().get_pixel(0, 39));
+        assert_eq!(canvas.rotate(-270.0), quarter);
+        assert_eq!(&canvas.rotate(360.0), canvas.image());
+
+        // interpolation agrees with the exact rotations
+        let close = |a: &GrayImage, b: &GrayImage| {
+            a.dimensions() == b.dimensions()
+                && a.pixels()
+                    .zip(b.pixels())
+                    .all(|(p, q)| p.0[0].abs_diff(q.0[0]) <= 1)
+        };
+        assert!(close(&canvas.rotate_bilinear(90.0), &quarter));
+        assert!(close(&canvas.rotate_bilinear(360.0), canvas.image()));
+
+        // arbitrary angles grow the image, with background corners
+        let tilted = canvas.rotate(30.0);
+        assert!(tilted.width() > 60 && tilted.height() > 40);
+        assert_eq!(tilted.get_pixel(0, 0).0[0], 220);
+        assert!(tilted.pixels().any(|p| p.0[0] < 128));
+    }
+
+    #[test]
+    fn set_xy_min() {
+        let mut canvas = Canvas::new([60; 2], [50; 2]);
+        canvas.set_xy_min(3, 4, 200);
+        canvas.set_xy_min(3, 4, 50);
+        assert_eq!(canvas.get_xy(3, 4), 50);
+        canvas.set_xy(5, 6, 200);
+        canvas.set_xy(5, 6, 50);
+        assert_eq!(canvas.get_xy(5, 6), 50);
+
+        // the order of writes only matters when overwriting
+        canvas.set_xy_min(3, 4, 200);
+        assert_eq!(canvas.get_xy(3, 4), 50);
+        canvas.set_xy(5, 6, 200);
+        assert_eq!(canvas.get_xy(5, 6), 200);
+        canvas.set_xy_min(-1, -1, 0);
+    }
+
+    #[test]
+    fn darken() {
+        let mut canvas = Canvas::new([60; 2], [50; 2]);
+        canvas.set_xy(20, 25, 0);
+        Sine::new(Point::new(0, 25), 20, 10).draw_with_value(&mut Darken(&mut canvas), 128);
+        assert_eq!(canvas.get_xy(20, 25), 0);
+        assert_eq!(canvas.get_xy(0, 25), 128);
+    }
+
+    #[test]
+    #[cfg(feature = "png")]
+    fn canvas16() {
+        let path = std::env::temp_dir().join("sineart_canvas16.png");
+        let mut canvas = Canvas16::new([60; 2], [50; 2]);
+        canvas.set_xy(3, 4, 30000);
+        AngledLine::new(Point::new(5, 5), Point::new(45, 18)).draw_antialiased(&mut canvas);
+        canvas.save(&path).unwrap();
+
+        let image = image::open(&path).unwrap().into_luma16();
+        assert_eq!(image.get_pixel(8, 50).0[0], 30000);
+        assert_eq!(&image, canvas.image());
+        // antialiased shades use the whole 16-bit range, not just multiples of 257
+        assert!(canvas.image().pixels().any(|p| p.0[0] % 257 != 0));
+    }
+
+    #[test]
+    fn to_braille() {
+        let mut canvas = Canvas::new([60, 80], [40, 60]);
+        assert_eq!(canvas.to_braille(0), "");
+        assert_eq!(
+            canvas.to_braille(30),
+            format!("{}\n", "\u{2800}".repeat(30)).repeat(10)
+        );
+
+        // a line along the bottom of the inner image ends up in the last row, lowest dots only
+        canvas.set_horizontal_line(&Point::new(30, 0), 0, 30);
+        let text = canvas.to_braille(30);
+        let rows: Vec<_> = text.lines().collect();
+        assert_eq!(rows.len(), 10);
+        assert!(rows[..9]
+            .iter()
+            .all(|row| row.chars().all(|c| c == '\u{2800}')));
+        assert!(rows[9].chars().all(|c| c == '\u{28C0}'));
+        assert!(text
+            .chars()
+            .all(|c| c == '\n' || ('\u{2800}'..='\u{28FF}').contains(&c)));
+    }
+
+    #[test]
+    fn to_braille_off_centre() {
+        // 8 rows of border above the inner image and only 2 below it
+        let white = GrayImage::from_pixel(60, 40, Luma([255]));
+        let mut canvas = Canvas::from_image(white, [30, 50], [2, 5]).unwrap();
+        canvas.set_horizontal_line(&Point::new(25, 0), 0, 25);
+        canvas.set_horizontal_line(&Point::new(25, 29), 0, 25);
+
+        // the bottom line fills the lowest dots of the last row, the top one the two highest of
+        // the first row, as there are more rows of dots than of pixels
+        let text = canvas.to_braille(25);
+        let rows: Vec<_> = text.lines().collect();
+        assert_eq!(rows.len(), 8);
+        assert!(rows[0].chars().all(|c| c == '\u{281B}'), "{}", text);
+        assert!(rows[1..7]
+            .iter()
+            .all(|row| row.chars().all(|c| c == '\u{2800}')));
+        assert!(rows[7].chars().all(|c| c == '\u{28C0}'), "{}", text);
+    }
+
+    #[test]
+    fn upscaled() {
+        let mut canvas = Canvas::new([60, 80], [50, 70]);
+        canvas.set_xy(3, 4, 0);
+        let large = canvas.upscaled(3);
+        assert_eq!((large.fw, large.fh, large.ow, large.oh), (240, 180, 15, 15));
+        assert!((9..12).all(|x| (12..15).all(|y| large.get_xy(x, y) == 0)));
+        assert_eq!(large.get_xy(12, 12), 255);
+
+        let mut small = Canvas::new([60, 80], [50, 70]);
+        small.downscale_from(&large);
+        assert!(small.get_xy(3, 4) < 128);
+        assert_eq!(small.get_xy(10, 10), 255);
+    }
+
+    #[test]
+    #[cfg(feature = "png")]
+    fn save_to_writer() {
+        let mut canvas = Canvas::new([60, 80], [50, 70]);
+        Sine::new(Point::new(0, 25), 20, 10).draw(&mut canvas);
+        let mut png = Vec::new();
+        canvas.save_to_writer(&mut png, ImageFormat::Png).unwrap();
+
+        let decoded = image::load_from_memory_with_format(&png, ImageFormat::Png)
+            .unwrap()
+            .into_luma8();
+        assert_eq!(decoded.dimensions(), (80, 60));
+        assert_eq!(&decoded, canvas.image());
+    }
+
+    #[test]
+    fn save_invalid_path() {
+        let canvas = Canvas::new([60; 2], [50; 2]);
+        let path = std::env::temp_dir().join("sineart_missing_dir/canvas.png");
+        assert!(canvas.save(path).is_err());
+    }
+
+    /// Only runs when building without JPEG support, e.g. `--no-default-features --features png`.
+    #[test]
+    #[cfg(all(feature = "png", not(feature = "jpeg")))]
+    fn save_without_codec() {
+        let canvas = Canvas::new([60; 2], [50; 2]);
+        let path = std::env::temp_dir().join("sineart_no_codec.jpg");
+        let result = canvas.save(&path);
+        assert!(
+            matches!(result, Err(image::ImageError::Unsupported(_))),
+            "{:?}",
+            result
+        );
+        canvas.save(path.with_extension("png")).unwrap();
+    }
+
+    #[test]
+    fn red_sine() {
+        let mut canvas = RgbCanvas::new([120; 2], [100; 2], Rgb([255, 255, 255]));
+        canvas.ink = Rgb([255, 0, 0]);
+        Sine::new(Point::new(0, 50), 20, 10).draw(&mut canvas);
+
+        // start of the sine, flipped into image coordinates
+        assert_eq!(
+            *canvas.image.get_pixel(10, 120 - 1 - 50 - 10),
+            Rgb([255, 0, 0])
+        );
+        assert_eq!(*canvas.image.get_pixel(0, 0), Rgb([255, 255, 255]));
+    }
+
+    #[test]
+    fn background() {
+        let canvas = Canvas::with_background([30, 40], [20, 30], 160);
+        for (x, y) in [(0, 0), (39, 0), (0, 29), (39, 29)] {
+            assert_eq!(canvas.image.get_pixel(x, y).0[0], 160);
+        }
+        assert_eq!(canvas.ink(), 0);
+        assert_eq!(Canvas::with_background([30, 40], [20, 30], 0).ink(), 255);
+    }
+
+    #[test]
+    fn out_of_bounds() {
+        let mut canvas = Canvas::new([20; 2], [10; 2]);
+        assert!(canvas.try_set_xy(0, 0, 0));
+        assert!(!canvas.try_set_xy(1000, 0, 0));
+        assert!(!canvas.try_set_xy(0, 1000, 0));
+        assert!(!canvas.try_set_xy(i32::MAX, i32::MAX, 0));
+        assert!(!canvas.try_set_xy(-1, 0, 0));
+        assert!(!canvas.try_set_xy(0, -1, 0));
+        canvas.set_horizontal_line(&Point::new(5, 5), 0, 1000);
+        canvas.set_vertical_line(&Point::new(5, 5), 0, 1000);
+        assert_eq!(canvas.try_get_xy(1000, 1000), None);
+        assert_eq!(canvas.try_get_xy(0, 0), Some(0));
+    }
+
+    #[test]
+    fn transposed() {
+        let mut canvas = Canvas::new([20; 2], [10; 2]);
+        Transposed(&mut canvas).set_xy(2, 7, 0);
+        assert_eq!(canvas.image.get_pixel(5 + 7, 20 - 1 - 2 - 5).0[0], 0);
     }
 
-    fn save<P: AsRef<Path>>(&self, path: P) {
-        self.image.save(path).expect("failed to save image");
+    #[test]
+    fn translated() {
+        let mut canvas = Canvas::new([20; 2], [10; 2]);
+        Translated(&mut canvas, Point::new(3, 4)).set_xy(2, 1, 0);
+        assert_eq!(canvas.get_xy(5, 5), 0);
+        assert!(!Translated(&mut canvas, Point::new(-3, 0)).try_set_xy(2, 1, 0));
     }
 }
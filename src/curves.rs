@@ -1,17 +1,182 @@
+pub mod bezier;
+pub mod circle;
 pub mod lines;
+pub mod lissajous;
+pub mod sawtooth;
 pub mod sine;
+pub mod square;
+pub mod triangle;
 
 use crate::canvas::XYDrawable;
+use image::{ImageFormat, ImageResult};
 use num::{Signed, ToPrimitive};
-use std::fmt::Display;
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display},
+    io::Write,
+    ops::{Add, Mul, Sub},
+};
+
+/// Gray level sample of a canvas pixel, which antialiasing can shade.
+pub trait Gray: Copy + Into<f64> {
+    /// White, the largest value.
+    const WHITE: Self;
+
+    /// Nearest gray level to a value in the range of the type.
+    fn from_f64(value: f64) -> Self;
+}
+
+impl Gray for u8 {
+    const WHITE: u8 = u8::MAX;
+
+    fn from_f64(value: f64) -> u8 {
+        value.round() as u8
+    }
+}
+
+impl Gray for u16 {
+    const WHITE: u16 = u16::MAX;
+
+    fn from_f64(value: f64) -> u16 {
+        value.round() as u16
+    }
+}
+
+/// Gray level of a pixel `distance` pixels away from the ideal curve, drawn with `ink` over
+/// `background`: the ink on the curve, fading linearly to the background one pixel away.
+pub fn blend<G: Gray>(ink: G, background: G, distance: f64) -> G {
+    let (ink, background) = (ink.into(), background.into());
+    G::from_f64(ink + (background - ink) * distance.clamp(0.0, 1.0))
+}
+
+/// Same as [`blend`] for black ink on a white background, over the full range of the type.
+pub fn antialiased_value<G: Gray>(distance: f64) -> G {
+    blend(G::from_f64(0.0), G::WHITE, distance)
+}
+
+/// Shade gray pixels given their distances to a curve, blending the canvas ink with their
+/// current value. Pixels listed several times are shaded once, for their smallest distance, and
+/// those outside the canvas are ignored.
+fn shade<D: XYDrawable>(canvas: &mut D, distances: impl IntoIterator<Item = ((i32, i32), f64)>)
+where
+    D::Value: Gray,
+{
+    let mut nearest = BTreeMap::new();
+    for (xy, distance) in distances {
+        let d: &mut f64 = nearest.entry(xy).or_insert(distance);
+        *d = d.min(distance);
+    }
+
+    let ink = canvas.ink();
+    for ((x, y), distance) in nearest {
+        if let Some(current) = canvas.try_get_xy(x, y) {
+            canvas.set_xy(x, y, blend(ink, current, distance));
+        }
+    }
+}
 
 /// Anything that is drawable onto a canvas.
 pub trait Drawable {
     /// Draw a single, non-antialiased line of thickness 1.
     fn draw(&self, canvas: &mut impl XYDrawable);
 
-    /// Draw a line of thickness `thickness`. The expansion is done on the horizontal axis.
-    fn draw_thick(&self, canvas: &mut impl XYDrawable, thickness: u32);
+    /// Draw a line of thickness `thickness`. The expansion is done on the horizontal axis, see
+    /// [`XYDrawable::set_horizontal_line`]: every pixel of the thin line is widened by
+    /// `thickness` pixels on either side, so a thickness of `t` gives a stroke `2t + 1` pixels
+    /// wide, measured horizontally.
+    ///
+    /// By default the thin line is drawn once for every horizontal offset, implementors should
+    /// override this if they can do better.
+    fn draw_thick(&self, canvas: &mut impl XYDrawable, thickness: u32) {
+        for dx in -(thickness as i32)..=thickness as i32 {
+            self.draw(&mut Shifted { canvas, dx });
+        }
+    }
+
+    /// Draw a line of thickness 1 on a gray canvas, shading the surrounding pixels between the
+    /// ink and their current value according to their distance to the ideal curve (see
+    /// [`blend`]). Pixels only ever move towards the ink, so crossing lines do not erase each
+    /// other.
+    ///
+    /// By default this is not antialiased at all and simply calls [`Drawable::draw`].
+    fn draw_antialiased<D: XYDrawable>(&self, canvas: &mut D)
+    where
+        D::Value: Gray,
+    {
+        self.draw(canvas);
+    }
+
+    /// Same as [`Drawable::draw_thick`] on a gray canvas, with the outer edges of the stroke
+    /// antialiased: the stroke is drawn solid, then the thin line is antialiased at both of its
+    /// horizontal extremes, see [`Drawable::draw_antialiased`]. The inside of the stroke stays
+    /// pure ink, so this only softens its outline.
+    fn draw_thick_antialiased<D: XYDrawable>(&self, canvas: &mut D, thickness: u32)
+    where
+        D::Value: Gray,
+    {
+        self.draw_thick(canvas, thickness);
+        let t = thickness as i32;
+        for dx in if t == 0 { vec![0] } else { vec![-t, t] } {
+            self.draw_antialiased(&mut Shifted { canvas, dx });
+        }
+    }
+
+    /// Same as [`Drawable::draw`], writing `value` instead of the canvas ink.
+    fn draw_with_value<D: XYDrawable>(&self, canvas: &mut D, value: D::Value) {
+        self.draw(&mut Inked { canvas, ink: value });
+    }
+}
+
+/// Drawable with its ink replaced.
+pub(crate) struct Inked<'a, D: XYDrawable> {
+    pub(crate) canvas: &'a mut D,
+    pub(crate) ink: D::Value,
+}
+
+impl<'a, D: XYDrawable> XYDrawable for Inked<'a, D> {
+    type Value = D::Value;
+
+    fn ink(&self) -> D::Value {
+        self.ink
+    }
+
+    fn try_get_xy(&self, x: i32, y: i32) -> Option<D::Value> {
+        self.canvas.try_get_xy(x, y)
+    }
+
+    fn try_set_xy(&mut self, x: i32, y: i32, value: D::Value) -> bool {
+        self.canvas.try_set_xy(x, y, value)
+    }
+
+    fn save_to_writer<W: Write>(&self, writer: &mut W, format: ImageFormat) -> ImageResult<()> {
+        self.canvas.save_to_writer(writer, format)
+    }
+}
+
+/// Drawable moved horizontally by `dx` pixels.
+struct Shifted<'a, D: XYDrawable> {
+    canvas: &'a mut D,
+    dx: i32,
+}
+
+impl<'a, D: XYDrawable> XYDrawable for Shifted<'a, D> {
+    type Value = D::Value;
+
+    fn ink(&self) -> D::Value {
+        self.canvas.ink()
+    }
+
+    fn try_get_xy(&self, x: i32, y: i32) -> Option<D::Value> {
+        self.canvas.try_get_xy(x + self.dx, y)
+    }
+
+    fn try_set_xy(&mut self, x: i32, y: i32, value: D::Value) -> bool {
+        self.canvas.try_set_xy(x + self.dx, y, value)
+    }
+
+    fn save_to_writer<W: Write>(&self, writer: &mut W, format: ImageFormat) -> ImageResult<()> {
+        self.canvas.save_to_writer(writer, format)
+    }
 }
 
 /// A line with a fixed gradient and direction, meaning the next possible pixel at each iteration
@@ -19,6 +184,36 @@ pub trait Drawable {
 /// that has positive derivative at all points, the next possible options at every step will be
 /// (x + 1, y), (x, y + 1) or (x + 1, y + 1). The approach is taken from
 /// http://members.chello.at/%7Eeasyfilter/Bresenham.pdf.
+///
+/// Every curve is [`Drawable`], so a custom one only needs its ends and implicit equation:
+///
+/// ```
+/// use sineart::{antialiased_value, Canvas, Curve, Drawable, Point, XYDrawable};
+///
+/// /// The parabola 40y = x², from the origin to (40, 40).
+/// struct Parabola(Point, Point);
+///
+/// impl Curve for Parabola {
+///     type T = i32;
+///
+///     fn start(&self) -> &Point {
+///         &self.0
+///     }
+///
+///     fn stop(&self) -> &Point {
+///         &self.1
+///     }
+///
+///     fn equation(&self, point: &Point) -> i32 {
+///         40 * point.y - point.x * point.x
+///     }
+/// }
+///
+/// let parabola = Parabola(Point::new(0, 0), Point::new(40, 40));
+/// let mut canvas = Canvas::new([60; 2], [50; 2]);
+/// parabola.draw_antialiased(&mut canvas);
+/// assert_eq!(canvas.get_xy(20, 10), antialiased_value::<u8>(0.0));
+/// ```
 pub trait Curve {
     /// Type to use in error functions, returned by equation etc.
     type T: Signed + PartialOrd + ToPrimitive + Display + core::fmt::Debug;
@@ -28,8 +223,158 @@ pub trait Curve {
 
     /// Implicit equation of curve, f(x, y) = 0.
     fn equation(&self, point: &Point) -> Self::T;
+
+    /// Estimated distance from a point to the curve, in pixels. By default this is the first
+    /// order approximation |f| / |grad f|, with the gradient taken as a forward difference.
+    fn distance(&self, point: &Point) -> f64 {
+        let f = |p: &Point| self.equation(p).to_f64().expect("could not convert to f64");
+        let value = f(point);
+        let dfdx = f(&(*point + Point::new(1, 0))) - value;
+        let dfdy = f(&(*point + Point::new(0, 1))) - value;
+        let gradient = dfdx.hypot(dfdy);
+        if gradient == 0.0 {
+            value.abs()
+        } else {
+            value.abs() / gradient
+        }
+    }
+
+    /// Pixels to shade when antialiasing, with their distance to the curve, see
+    /// [`Drawable::draw_antialiased`]. By default these are the neighbourhoods of the marched
+    /// points, pixels may be listed several times.
+    fn antialiased_distances(&self) -> Vec<((i32, i32), f64)> {
+        self.points()
+            .into_iter()
+            .flat_map(|point| {
+                (-1..=1).flat_map(move |dx| (-1..=1).map(move |dy| point + Point::new(dx, dy)))
+            })
+            .map(|p| ((p.x, p.y), self.distance(&p)))
+            .collect()
+    }
+
+    /// Pixels traced by the curve from start to stop, as visited by the marcher. Once next to
+    /// `stop` the marcher always steps onto it, and if it misses it altogether it ends as soon as
+    /// it moves past it on either axis, see [`Curve::try_points`] to tell when that happens.
+    /// Every step gets closer to `stop` on at least one axis, so this takes at most as many steps
+    /// as the Manhattan distance between start and stop. Steps whose errors are equal, or only
+    /// differ by float rounding, are tied, and ties always go to the diagonal step, then the
+    /// horizontal one, so the same curve gives the same pixels on every platform.
+    fn points(&self) -> Vec<Point> {
+        self.try_points().unwrap_or_else(|missed| missed.points)
+    }
+
+    /// Same as [`Curve::points`], failing if the marcher missed `stop`, with the pixels it
+    /// traced before giving up.
+    fn try_points(&self) -> Result<Vec<Point>, MissedStop> {
+        let (start, stop) = (*self.start(), *self.stop());
+        let slope = Slope::between(&start, &stop);
+        let max_steps = start.manhattan_distance(&stop);
+        let mut current = start;
+        let mut points = vec![current];
+
+        for _ in 0..max_steps {
+            if current == stop {
+                break;
+            }
+            // next to the stop, land on it even if the equation would rather step around it
+            if current.manhattan_distance(&stop) == 1 {
+                current = stop;
+                points.push(current);
+                break;
+            }
+            current = slope.best(&current, |p| {
+                self.equation(p)
+                    .abs()
+                    .to_f64()
+                    .expect("could not convert to f64")
+            });
+            if slope.past(&current, &stop) {
+                break;
+            }
+            points.push(current);
+        }
+        if current != stop {
+            return Err(MissedStop { stop, points });
+        }
+        Ok(points)
+    }
+
+    /// Coarser version of [`Curve::points`] for quick previews: the curve is only sampled every
+    /// `step` pixels or so, picking the best of the points `step` pixels away along either axis,
+    /// and the samples are joined by straight segments. Start and stop are always included, and
+    /// a `step` of 0 or 1 is the same as [`Curve::points`].
+    fn points_step(&self, step: u32) -> Vec<Point> {
+        if step <= 1 {
+            return self.points();
+        }
+        let (start, stop) = (*self.start(), *self.stop());
+        let slope = Slope::between(&start, &stop);
+        let (h, v) = slope.steps();
+        let step = step as i32;
+        let mut current = start;
+        let mut points = vec![current];
+
+        while current != stop {
+            let remaining = stop - current;
+            let next = if remaining.x.abs() <= step && remaining.y.abs() <= step {
+                stop
+            } else {
+                // points at a chessboard distance of `step`, in order along the edges of that
+                // square and short of the stop on both axes
+                let candidates: Vec<_> = (0..=step)
+                    .map(|i| current + h * step + v * i)
+                    .chain((0..step).rev().map(|i| current + h * i + v * step))
+                    .filter(|p| !slope.past(p, &stop))
+                    .collect();
+                crossing(self, &candidates)
+            };
+            points.extend(
+                lines::AngledLine::new(current, next)
+                    .points()
+                    .into_iter()
+                    .skip(1),
+            );
+            current = next;
+        }
+        points
+    }
+
+    /// Same as [`Drawable::draw`], from the coarser [`Curve::points_step`].
+    fn draw_step(&self, canvas: &mut impl XYDrawable, step: u32) {
+        let ink = canvas.ink();
+        for point in self.points_step(step) {
+            canvas.set_point(&point, ink);
+        }
+    }
+}
+
+/// Point closest to where a curve crosses a path of candidates, found by bisecting on the sign
+/// of its equation. Since curves are monotonic, they cross the edges of a square around one of
+/// their points only once, so this takes a logarithmic number of evaluations.
+fn crossing<C: Curve + ?Sized>(curve: &C, candidates: &[Point]) -> Point {
+    let f = |i: usize| curve.equation(&candidates[i]);
+    let (mut lo, mut hi) = (0, candidates.len() - 1);
+    let negative = f(lo).is_negative();
+    while hi - lo > 1 {
+        let mid = (lo + hi) / 2;
+        if f(mid).is_negative() == negative {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    if f(hi).abs() < f(lo).abs() {
+        candidates[hi]
+    } else {
+        candidates[lo]
+    }
 }
 
+/// Relative difference between the errors of two candidate points below which the marcher
+/// considers them equally good, see [`Slope::best`]. Far above float rounding, far below any
+/// meaningful difference in pixels.
+const TIE_TOLERANCE: f64 = 1e-9;
+
 /// Potential direction of the curve, a mixture of start and stop ordering and of the derivative
 /// value for its entire length.
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -40,44 +385,119 @@ enum Slope {
     NorthWest,
 }
 
+/// Curve whose marcher never reached its stop, see [`Curve::try_points`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MissedStop {
+    pub stop: Point,
+    /// Pixels traced from the start until the marcher moved past the stop.
+    pub points: Vec<Point>,
+}
+
+impl fmt::Display for MissedStop {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "curve from {:?} missed its stop {:?}, ending at {:?}",
+            self.points[0],
+            self.stop,
+            self.points[self.points.len() - 1]
+        )
+    }
+}
+
+impl std::error::Error for MissedStop {}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Point {
-    pub x: u32,
-    pub y: u32,
+    pub x: i32,
+    pub y: i32,
 }
 
 impl Point {
-    pub fn new(x: u32, y: u32) -> Self {
+    pub fn new(x: i32, y: i32) -> Self {
         Self { x, y }
     }
+
+    pub fn origin() -> Self {
+        Self::new(0, 0)
+    }
+
+    /// Number of horizontal and vertical steps needed to go from one point to the other.
+    pub fn manhattan_distance(&self, other: &Point) -> i32 {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Mul<i32> for Point {
+    type Output = Point;
+
+    fn mul(self, factor: i32) -> Point {
+        Point::new(self.x * factor, self.y * factor)
+    }
 }
 
 impl Slope {
-    /// Select the next possible three points to be drawn.
+    /// Unit steps along X and Y in this direction.
+    fn steps(&self) -> (Point, Point) {
+        let (sx, sy) = match self {
+            Slope::NorthEast => (1, 1),
+            Slope::SouthEast => (1, -1),
+            Slope::SouthWest => (-1, -1),
+            Slope::NorthWest => (-1, 1),
+        };
+        (Point::new(sx, 0), Point::new(0, sy))
+    }
+
+    /// Select the next possible three points to be drawn, in order of preference when their
+    /// errors tie: the diagonal step, then the horizontal one, then the vertical one.
     fn next(&self, point: &Point) -> [Point; 3] {
-        let Point { x, y } = *point;
-        match self {
-            Slope::NorthEast => [
-                Point::new(x, y + 1),
-                Point::new(x + 1, y + 1),
-                Point::new(x + 1, y),
-            ],
-            Slope::SouthEast => [
-                Point::new(x + 1, y),
-                Point::new(x + 1, y - 1),
-                Point::new(x, y - 1),
-            ],
-            Slope::SouthWest => [
-                Point::new(x, y - 1),
-                Point::new(x - 1, y - 1),
-                Point::new(x - 1, y),
-            ],
-            Slope::NorthWest => [
-                Point::new(x - 1, y),
-                Point::new(x - 1, y + 1),
-                Point::new(x, y + 1),
-            ],
-        }
+        let p = *point;
+        let (h, v) = self.steps();
+        [p + h + v, p + h, p + v]
+    }
+
+    /// Next point to be drawn, the one of [`Slope::next`] with the smallest error. Errors
+    /// within [`TIE_TOLERANCE`] of the smallest count as equal to it, and ties go to the first
+    /// of them in order of preference, so the same curve is traced the same way on every
+    /// platform even though its equation may round differently.
+    fn best(&self, point: &Point, error: impl Fn(&Point) -> f64) -> Point {
+        let candidates = self.next(point).map(|p| {
+            let e = error(&p);
+            assert!(!e.is_nan(), "NaN encountered");
+            (p, e)
+        });
+        let smallest = candidates
+            .iter()
+            .map(|&(_, e)| e)
+            .fold(f64::INFINITY, f64::min);
+        candidates
+            .into_iter()
+            .find(|&(_, e)| e - smallest <= TIE_TOLERANCE * smallest.max(1.0))
+            .map(|(p, _)| p)
+            .expect("no viable next point found")
+    }
+
+    /// Whether a point has moved beyond `stop` on either axis, going in this direction.
+    fn past(&self, point: &Point, stop: &Point) -> bool {
+        let (h, v) = self.steps();
+        let beyond = *point - *stop;
+        beyond.x * h.x > 0 || beyond.y * v.y > 0
     }
 
     /// Determines the slope type, assuming the derivative does not change sign.
@@ -98,43 +518,286 @@ impl Slope {
 
 impl<C: Curve> Drawable for C {
     fn draw(&self, canvas: &mut impl XYDrawable) {
-        let mut current = *self.start();
-        let slope = Slope::between(self.start(), self.stop());
-
-        while &current != self.stop() {
-            canvas.set_point(&current, 0);
-            current = slope
-                .next(&current)
-                .into_iter()
-                .map(|p| (p, self.equation(&p).abs()))
-                .min_by(|(_, a), (_, b)| a.partial_cmp(b).expect("NaN encountered"))
-                .map(|(p, _)| p)
-                .expect("no viable next point found");
+        let ink = canvas.ink();
+        for point in self.points() {
+            canvas.set_point(&point, ink);
         }
-        canvas.set_point(&current, 0);
     }
 
     fn draw_thick(&self, canvas: &mut impl XYDrawable, thickness: u32) {
-        let mut current = *self.start();
-        let slope = Slope::between(self.start(), self.stop());
+        let ink = canvas.ink();
+        for point in self.points() {
+            canvas.set_horizontal_line(&point, ink, thickness);
+        }
+    }
+
+    fn draw_antialiased<D: XYDrawable>(&self, canvas: &mut D)
+    where
+        D::Value: Gray,
+    {
+        shade(canvas, self.antialiased_distances());
+    }
 
-        while &current != self.stop() {
-            canvas.set_horizontal_line(&current, 0, thickness);
-            current = slope
-                .next(&current)
-                .into_iter()
-                .map(|p| (p, self.equation(&p).abs()))
-                .min_by(|(_, a), (_, b)| a.partial_cmp(b).expect("NaN encountered"))
-                .map(|(p, _)| p)
-                .expect("no viable next point found");
+    /// The core of the stroke is drawn solid, then the pixels around it are shaded by how far
+    /// they fall outside the ideal stroke: either horizontally past `thickness`, as in
+    /// [`Drawable::draw_thick`], or perpendicular to the curve where it runs flat.
+    fn draw_thick_antialiased<D: XYDrawable>(&self, canvas: &mut D, thickness: u32)
+    where
+        D::Value: Gray,
+    {
+        if thickness == 0 {
+            self.draw_antialiased(canvas);
+            return;
         }
-        canvas.set_horizontal_line(&current, 0, thickness);
+        self.draw_thick(canvas, thickness - 1);
+
+        let t = thickness as i32;
+        let f = |p: &Point| self.equation(p).to_f64().expect("could not convert to f64");
+        let horizontal = |p: &Point| {
+            let dfdx = (f(&(*p + Point::new(1, 0))) - f(p)).abs();
+            if dfdx == 0.0 {
+                f64::INFINITY
+            } else {
+                f(p).abs() / dfdx
+            }
+        };
+        let distances: Vec<_> = self
+            .points()
+            .into_iter()
+            .flat_map(|point| {
+                (-t - 1..=t + 1)
+                    .flat_map(move |dx| (-1..=1).map(move |dy| point + Point::new(dx, dy)))
+            })
+            .map(|p| {
+                let outside = (horizontal(&p) - thickness as f64).max(0.0);
+                ((p.x, p.y), self.distance(&p).min(outside))
+            })
+            .collect();
+        shade(canvas, distances);
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::canvas::Canvas;
+
+    /// Vertical segment only implementing `draw`, to exercise the default `draw_thick`.
+    struct Post(Point, i32);
+
+    impl Drawable for Post {
+        fn draw(&self, canvas: &mut impl XYDrawable) {
+            let ink = canvas.ink();
+            for y in self.0.y..=self.0.y + self.1 {
+                canvas.set_xy(self.0.x, y, ink);
+            }
+        }
+    }
+
+    /// Number of set pixels on a row of the canvas.
+    fn width(canvas: &Canvas, y: i32) -> usize {
+        (0..canvas.iw as i32)
+            .filter(|&x| canvas.get_xy(x, y) == 0)
+            .count()
+    }
+
+    #[test]
+    fn draw_thick_default() {
+        let mut thin = Canvas::new([60; 2], [50; 2]);
+        Post(Point::new(20, 10), 20).draw_thick(&mut thin, 1);
+        let mut thick = Canvas::new([60; 2], [50; 2]);
+        Post(Point::new(20, 10), 20).draw_thick(&mut thick, 3);
+
+        assert_eq!(width(&thin, 15), 3);
+        assert_eq!(width(&thick, 15), 7);
+        assert_eq!(width(&thick, 35), 0);
+    }
+
+    #[test]
+    fn antialiased_values() {
+        assert_eq!(antialiased_value::<u8>(0.0), 0);
+        assert_eq!(antialiased_value::<u8>(0.5), 128);
+        assert_eq!(antialiased_value::<u8>(3.0), 255);
+        assert_eq!(blend(255u8, 0, 0.25), 191);
+        assert_eq!(antialiased_value::<u16>(0.5), 32768);
+        assert_eq!(antialiased_value::<u16>(3.0), u16::MAX);
+    }
+
+    #[test]
+    fn draw_with_value() {
+        let line = lines::AngledLine::new(Point::new(0, 0), Point::new(40, 30));
+        let mut canvas = Canvas::new([60; 2], [50; 2]);
+        line.draw_with_value(&mut canvas, 128);
+        assert_eq!(canvas.get_xy(0, 0), 128);
+        assert_eq!(canvas.get_xy(40, 30), 128);
+        assert_eq!(canvas.ink, 0);
+
+        // antialiasing scales between the ink and the background, never beyond
+        let mut canvas = Canvas::new([60; 2], [50; 2]);
+        canvas.ink = 128;
+        line.draw_antialiased(&mut canvas);
+        assert_eq!(canvas.get_xy(40, 30), 128);
+        assert!((0..50).all(|x| (0..50).all(|y| canvas.get_xy(x, y) >= 128)));
+    }
+
+    /// Line from the origin to (40, 30), only using the default antialiasing.
+    struct Slanted;
+
+    impl Curve for Slanted {
+        type T = i32;
+
+        fn start(&self) -> &Point {
+            &Point { x: 0, y: 0 }
+        }
+
+        fn stop(&self) -> &Point {
+            &Point { x: 40, y: 30 }
+        }
+
+        fn equation(&self, point: &Point) -> i32 {
+            3 * point.x - 4 * point.y
+        }
+    }
+
+    #[test]
+    fn draw_antialiased_curve() {
+        let line = Slanted;
+        let mut canvas = Canvas::new([60; 2], [50; 2]);
+        line.draw_antialiased(&mut canvas);
+
+        assert_eq!(canvas.get_xy(0, 0), 0);
+        assert_eq!(canvas.get_xy(40, 30), 0);
+        let shades = (0..50)
+            .flat_map(|x| (0..50).map(move |y| (x, y)))
+            .filter(|&(x, y)| canvas.get_xy(x, y) != 0 && canvas.get_xy(x, y) != 255)
+            .count();
+        assert!(shades > 0);
+    }
+
+    #[test]
+    fn draw_thick_curve() {
+        let line = lines::AngledLine::new(Point::new(0, 0), Point::new(40, 30));
+        let mut thin = Canvas::new([60; 2], [50; 2]);
+        line.draw_thick(&mut thin, 1);
+        let mut thick = Canvas::new([60; 2], [50; 2]);
+        line.draw_thick(&mut thick, 3);
+
+        assert!(width(&thick, 15) > width(&thin, 15));
+    }
+
+    /// Parabola y = x^2 / 40 from the origin to (40, 40), counting how often it is evaluated.
+    #[derive(Default)]
+    struct Parabola(std::cell::Cell<usize>);
+
+    impl Curve for Parabola {
+        type T = i32;
+
+        fn start(&self) -> &Point {
+            &Point { x: 0, y: 0 }
+        }
+
+        fn stop(&self) -> &Point {
+            &Point { x: 40, y: 40 }
+        }
+
+        fn equation(&self, point: &Point) -> i32 {
+            self.0.set(self.0.get() + 1);
+            40 * point.y - point.x * point.x
+        }
+    }
+
+    #[test]
+    fn draw_step() {
+        let parabola = Parabola::default();
+        let mut exact = Canvas::new([60; 2], [50; 2]);
+        parabola.draw(&mut exact);
+        let exact_evaluations = parabola.0.replace(0);
+        let mut stepped = Canvas::new([60; 2], [50; 2]);
+        parabola.draw_step(&mut stepped, 1);
+        assert_eq!(exact, stepped);
+
+        // the segments are still connected, so the savings are in evaluating the curve
+        parabola.0.set(0);
+        let mut coarse = Canvas::new([60; 2], [50; 2]);
+        parabola.draw_step(&mut coarse, 8);
+        assert!(parabola.0.get() < exact_evaluations / 2);
+        assert_eq!(coarse.get_xy(0, 0), 0);
+        assert_eq!(coarse.get_xy(40, 40), 0);
+        assert_eq!(coarse.get_xy(20, 10), 0);
+    }
+
+    #[test]
+    fn points() {
+        let line = lines::AngledLine::new(Point::new(2, 3), Point::new(40, 30));
+        let points = line.points();
+        assert_eq!(points.first(), Some(&Point::new(2, 3)));
+        assert_eq!(points.last(), Some(&Point::new(40, 30)));
+        assert!(points.windows(2).all(|pair| pair[0].x <= pair[1].x));
+        assert!(points.windows(2).all(|pair| pair[0].y <= pair[1].y));
+    }
+
+    /// Horizontal line through `start` that claims to stop somewhere off it.
+    struct Stray(Point, Point);
+
+    impl Curve for Stray {
+        type T = i32;
+
+        fn start(&self) -> &Point {
+            &self.0
+        }
+
+        fn stop(&self) -> &Point {
+            &self.1
+        }
+
+        fn equation(&self, point: &Point) -> i32 {
+            point.y - self.0.y
+        }
+    }
+
+    #[test]
+    fn unreachable_stop() {
+        let stray = Stray(Point::new(0, 0), Point::new(10, 5));
+        let points = stray.points();
+        assert_eq!(points.first(), Some(&Point::new(0, 0)));
+        assert_eq!(points.last(), Some(&Point::new(10, 0)));
+        assert!(points.len() <= 16);
+
+        let missed = stray.try_points().unwrap_err();
+        assert_eq!(missed.stop, Point::new(10, 5));
+        assert_eq!(missed.points, points);
+        assert!(Stray(Point::new(0, 0), Point::new(10, 0))
+            .try_points()
+            .is_ok());
+    }
+
+    #[test]
+    fn adjacent_stop() {
+        // the line never reaches its stop, one pixel above its end, but still ends on it
+        let points = Stray(Point::new(0, 0), Point::new(10, 1)).points();
+        assert_eq!(points.last(), Some(&Point::new(10, 1)));
+        assert_eq!(points[points.len() - 2], Point::new(10, 0));
+
+        let sine = sine::Sine::new(Point::new(0, 100), 37, 3);
+        let mut canvas = Canvas::new([220; 2], [200; 2]);
+        sine.draw(&mut canvas);
+        for stop in [(3, 137), (6, 100), (9, 63), (12, 100)] {
+            assert_eq!(canvas.get_xy(stop.0, stop.1), 0);
+        }
+    }
+
+    #[test]
+    fn point_ops() {
+        let a = Point::new(3, -2);
+        let b = Point::new(5, 4);
+        assert_eq!(a + b, Point::new(8, 2));
+        assert_eq!(a - b, Point::new(-2, -6));
+        assert_eq!(a * -3, Point::new(-9, 6));
+        assert_eq!(a + Point::origin(), a);
+        assert_eq!(a.manhattan_distance(&b), 8);
+        assert_eq!(b.manhattan_distance(&a), 8);
+        assert_eq!(Point::origin().manhattan_distance(&Point::new(-4, -1)), 5);
+    }
 
     mod slope {
         use super::*;
@@ -157,8 +820,33 @@ mod tests {
             let slope = Slope::NorthEast;
             assert_eq!(
                 slope.next(&point),
-                [Point::new(0, 1), Point::new(1, 1), Point::new(1, 0)]
+                [Point::new(1, 1), Point::new(1, 0), Point::new(0, 1)]
             )
         }
+
+        #[test]
+        fn ties() {
+            let origin = Point::new(0, 0);
+            let (diagonal, horizontal, vertical) =
+                (Point::new(1, 1), Point::new(1, 0), Point::new(0, 1));
+
+            // the line 2y = x is as far from the diagonal step as from the horizontal one
+            let line = |p: &Point| (2 * p.y - p.x).abs() as f64;
+            assert_eq!(Slope::NorthEast.best(&origin, line), diagonal);
+
+            // symmetric about the diagonal, which is worse than either side
+            let valley = |p: &Point| if *p == diagonal { 2.0 } else { 1.0 };
+            assert_eq!(Slope::NorthEast.best(&origin, valley), horizontal);
+            let valley = |p: &Point| if p.x == p.y { 2.0 } else { 1.0 };
+            assert_eq!(Slope::SouthWest.best(&origin, valley), Point::new(-1, 0));
+
+            // errors only differing by float rounding still tie
+            let rounded = |p: &Point| match *p {
+                p if p == horizontal => 0.1 + 0.2,
+                p if p == vertical => 0.3,
+                _ => 1.0,
+            };
+            assert_eq!(Slope::NorthEast.best(&origin, rounded), horizontal);
+        }
     }
 }
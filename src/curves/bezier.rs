@@ -0,0 +1,114 @@
+use super::{shade, Drawable, Gray, Point};
+use crate::canvas::XYDrawable;
+
+/// Quadratic Bézier curve, B(t) = (1 - t)² P0 + 2t(1 - t) P1 + t² P2 for t in [0, 1]. It is drawn
+/// by sampling t finely enough that consecutive samples are less than a pixel apart, rather than
+/// with the marcher used for [`super::Curve`].
+pub struct QuadraticBezier {
+    p0: Point,
+    p1: Point,
+    p2: Point,
+}
+
+impl QuadraticBezier {
+    pub fn new(p0: Point, p1: Point, p2: Point) -> Self {
+        Self { p0, p1, p2 }
+    }
+
+    /// Point of the curve at parameter `t`.
+    fn at(&self, t: f64) -> (f64, f64) {
+        let [a, b, c] = [(1.0 - t).powi(2), 2.0 * t * (1.0 - t), t.powi(2)];
+        (
+            a * self.p0.x as f64 + b * self.p1.x as f64 + c * self.p2.x as f64,
+            a * self.p0.y as f64 + b * self.p1.y as f64 + c * self.p2.y as f64,
+        )
+    }
+
+    /// Samples along the curve, from P0 to P2 inclusive. The curve is never longer than its
+    /// control polygon, so twice the length of the latter guarantees sub-pixel spacing.
+    fn samples(&self) -> impl Iterator<Item = (f64, f64)> + '_ {
+        let length =
+            |a: &Point, b: &Point| (a.x as f64 - b.x as f64).hypot(a.y as f64 - b.y as f64);
+        let n = (2.0 * (length(&self.p0, &self.p1) + length(&self.p1, &self.p2))).ceil() as u32;
+        let n = n.max(1);
+        (0..=n).map(move |i| self.at(i as f64 / n as f64))
+    }
+}
+
+impl Drawable for QuadraticBezier {
+    fn draw(&self, canvas: &mut impl XYDrawable) {
+        let ink = canvas.ink();
+        for (x, y) in self.samples() {
+            canvas.set_xy(x.round() as i32, y.round() as i32, ink);
+        }
+    }
+
+    fn draw_thick(&self, canvas: &mut impl XYDrawable, thickness: u32) {
+        let ink = canvas.ink();
+        for (x, y) in self.samples() {
+            canvas.set_horizontal_line(
+                &Point::new(x.round() as i32, y.round() as i32),
+                ink,
+                thickness,
+            );
+        }
+    }
+
+    /// The distance from a pixel to the curve is estimated as the distance to the nearest sample.
+    fn draw_antialiased<D: XYDrawable>(&self, canvas: &mut D)
+    where
+        D::Value: Gray,
+    {
+        let distances = self.samples().flat_map(|(x, y)| {
+            let (px, py) = (x.round() as i32, y.round() as i32);
+            (px - 1..=px + 1).flat_map(move |nx| {
+                (py - 1..=py + 1).map(move |ny| ((nx, ny), (nx as f64 - x).hypot(ny as f64 - y)))
+            })
+        });
+        shade(canvas, distances);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::canvas::Canvas;
+
+    /// Check the endpoints are drawn and that nothing is drawn outside the bounding box of the
+    /// control points, widened by `margin`.
+    fn check(canvas: &Canvas, margin: i32) {
+        assert_eq!(canvas.get_xy(10, 10), 0);
+        assert_eq!(canvas.get_xy(90, 20), 0);
+        for x in 0..100 {
+            for y in 0..100 {
+                let inside = (10 - margin..=90 + margin).contains(&x)
+                    && (10 - margin..=80 + margin).contains(&y);
+                assert!(inside || canvas.get_xy(x, y) == 255, "({}, {})", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn endpoints_and_bounds() {
+        let bezier =
+            QuadraticBezier::new(Point::new(10, 10), Point::new(40, 80), Point::new(90, 20));
+
+        let mut canvas = Canvas::new([120; 2], [100; 2]);
+        bezier.draw(&mut canvas);
+        check(&canvas, 0);
+
+        let mut canvas = Canvas::new([120; 2], [100; 2]);
+        bezier.draw_antialiased(&mut canvas);
+        check(&canvas, 1);
+    }
+
+    #[test]
+    #[ignore = "visual check"]
+    fn bezier() {
+        let bezier =
+            QuadraticBezier::new(Point::new(0, 0), Point::new(50, 190), Point::new(190, 100));
+        let mut img = Canvas::new([240; 2], [200; 2]);
+        bezier.draw_antialiased(&mut img);
+        img.save("tests/bezier.bmp").unwrap();
+    }
+}
@@ -0,0 +1,119 @@
+use super::{Curve, Drawable, Gray, Point};
+use crate::canvas::XYDrawable;
+
+/// Full circle. Since the marcher used for [`Curve`] only follows paths whose direction does not
+/// change, the circle is drawn as four quarter arcs, each of which is a [`Curve`].
+pub struct Circle {
+    center: Point,
+    radius: u32,
+}
+
+/// Quarter of a circle, between two cardinal points.
+struct Arc {
+    start: Point,
+    stop: Point,
+    center: Point,
+    radius: u32,
+}
+
+impl Circle {
+    pub fn new(center: Point, radius: u32) -> Self {
+        Self { center, radius }
+    }
+
+    /// Return the four comprising arcs, anticlockwise from the eastmost point.
+    fn arcs(&self) -> [Arc; 4] {
+        let Point { x, y } = self.center;
+        let r = self.radius as i32;
+        let cardinals = [
+            Point::new(x + r, y),
+            Point::new(x, y + r),
+            Point::new(x - r, y),
+            Point::new(x, y - r),
+        ];
+        [0, 1, 2, 3].map(|i| Arc {
+            start: cardinals[i],
+            stop: cardinals[(i + 1) % 4],
+            center: self.center,
+            radius: self.radius,
+        })
+    }
+}
+
+impl Curve for Arc {
+    type T = i64;
+
+    fn start(&self) -> &Point {
+        &self.start
+    }
+
+    fn stop(&self) -> &Point {
+        &self.stop
+    }
+
+    fn equation(&self, point: &Point) -> Self::T {
+        let dx = point.x as i64 - self.center.x as i64;
+        let dy = point.y as i64 - self.center.y as i64;
+        dx * dx + dy * dy - (self.radius as i64).pow(2)
+    }
+}
+
+impl Drawable for Circle {
+    fn draw(&self, canvas: &mut impl XYDrawable) {
+        for arc in self.arcs().iter() {
+            arc.draw(canvas);
+        }
+    }
+
+    fn draw_thick(&self, canvas: &mut impl XYDrawable, thickness: u32) {
+        for arc in self.arcs().iter() {
+            arc.draw_thick(canvas, thickness);
+        }
+    }
+
+    fn draw_antialiased<D: XYDrawable>(&self, canvas: &mut D)
+    where
+        D::Value: Gray,
+    {
+        for arc in self.arcs().iter() {
+            arc.draw_antialiased(canvas);
+        }
+    }
+
+    fn draw_thick_antialiased<D: XYDrawable>(&self, canvas: &mut D, thickness: u32)
+    where
+        D::Value: Gray,
+    {
+        for arc in self.arcs().iter() {
+            arc.draw_thick_antialiased(canvas, thickness);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::canvas::Canvas;
+
+    #[test]
+    fn cardinal_points() {
+        let mut canvas = Canvas::new([120; 2], [100; 2]);
+        Circle::new(Point::new(50, 50), 30).draw(&mut canvas);
+
+        for (x, y) in [(80, 50), (50, 80), (20, 50), (50, 20)] {
+            assert_eq!(canvas.get_xy(x, y), 0);
+        }
+        assert_eq!(canvas.get_xy(50, 50), 255);
+        // point on the diagonal, at a distance of ~30 from the center
+        assert_eq!(canvas.get_xy(71, 71), 0);
+    }
+
+    #[test]
+    #[ignore = "visual check"]
+    fn circle() {
+        let circle = Circle::new(Point::new(100, 100), 80);
+        let mut img = Canvas::new([240; 2], [200; 2]);
+        circle.draw(&mut img);
+        img.save("tests/circle.bmp").unwrap();
+    }
+}
@@ -1,19 +1,71 @@
-//! Implemented only for debug purposes.
+//! Straight lines: angled ones for the ramps of triangle waves, and axis aligned ones for flat
+//! waves and the connectors between rows.
 
-use super::{Curve, Point};
+use super::{Curve, Drawable, Point};
+use crate::canvas::XYDrawable;
 
-struct AngledLine {
-    start: Point,
-    stop: Point,
+/// Horizontal segment `extent` pixels either side of its center, drawn in one go rather than
+/// marched like a [`Curve`]. Thickness widens it horizontally, as for any other stroke.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HorizontalLine {
+    pub center: Point,
+    pub extent: u32,
+}
+
+impl HorizontalLine {
+    pub fn new(center: Point, extent: u32) -> Self {
+        Self { center, extent }
+    }
+}
+
+impl Drawable for HorizontalLine {
+    fn draw(&self, canvas: &mut impl XYDrawable) {
+        self.draw_thick(canvas, 0);
+    }
+
+    fn draw_thick(&self, canvas: &mut impl XYDrawable, thickness: u32) {
+        let ink = canvas.ink();
+        canvas.set_horizontal_line(&self.center, ink, self.extent + thickness);
+    }
+}
+
+/// Vertical counterpart of [`HorizontalLine`], `extent` pixels above and below its center.
+/// Thickness widens it horizontally, into a rectangle.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VerticalLine {
+    pub center: Point,
+    pub extent: u32,
+}
+
+impl VerticalLine {
+    pub fn new(center: Point, extent: u32) -> Self {
+        Self { center, extent }
+    }
+}
+
+impl Drawable for VerticalLine {
+    fn draw(&self, canvas: &mut impl XYDrawable) {
+        self.draw_thick(canvas, 0);
+    }
+
+    fn draw_thick(&self, canvas: &mut impl XYDrawable, thickness: u32) {
+        let ink = canvas.ink();
+        for dx in -(thickness as i32)..=thickness as i32 {
+            canvas.set_vertical_line(&(self.center + Point::new(dx, 0)), ink, self.extent);
+        }
+    }
+}
+
+pub(crate) struct AngledLine {
+    pub(crate) start: Point,
+    pub(crate) stop: Point,
     dx: i32,
     dy: i32,
 }
 
 impl AngledLine {
-    #[allow(dead_code)]
-    fn new(start: Point, stop: Point) -> Self {
-        let dx = stop.x as i32 - start.x as i32;
-        let dy = stop.y as i32 - start.y as i32;
+    pub(crate) fn new(start: Point, stop: Point) -> Self {
+        let Point { x: dx, y: dy } = stop - start;
 
         Self {
             start,
@@ -36,8 +88,42 @@ impl Curve for AngledLine {
     }
 
     fn equation(&self, point: &Point) -> Self::T {
-        self.dx * (point.y as i32 - self.start.y as i32)
-            - (point.x as i32 - self.start.x as i32) * self.dy
+        let d = *point - self.start;
+        self.dx * d.y - d.x * self.dy
+    }
+
+    /// Xiaolin Wu's algorithm: at every step along the major axis, the two pixels straddling the
+    /// line share its coverage according to how close they are to it.
+    fn antialiased_distances(&self) -> Vec<((i32, i32), f64)> {
+        let steep = self.dy.abs() > self.dx.abs();
+        // work in (major, minor) coordinates
+        let (major, minor, dmajor, dminor) = if steep {
+            (self.start.y, self.start.x, self.dy, self.dx)
+        } else {
+            (self.start.x, self.start.y, self.dx, self.dy)
+        };
+        let gradient = if dmajor == 0 {
+            0.0
+        } else {
+            dminor as f64 / dmajor as f64
+        };
+
+        let mut distances = Vec::new();
+        for i in 0..=dmajor.abs() {
+            let i = i * dmajor.signum();
+            let exact = minor as f64 + gradient * i as f64;
+            let below = exact.floor();
+            let fraction = exact - below;
+            for (m, distance) in [(below as i32, fraction), (below as i32 + 1, 1.0 - fraction)] {
+                let xy = if steep {
+                    (m, major + i)
+                } else {
+                    (major + i, m)
+                };
+                distances.push((xy, distance));
+            }
+        }
+        distances
     }
 }
 
@@ -49,12 +135,76 @@ mod tests {
         curves::Drawable,
     };
 
+    /// Total coverage of a set of pixels, 1 for each black one.
+    fn coverage(canvas: &Canvas, pixels: impl Iterator<Item = (i32, i32)>) -> f64 {
+        pixels
+            .map(|(x, y)| (255 - canvas.get_xy(x, y)) as f64 / 255.0)
+            .sum()
+    }
+
+    #[test]
+    fn wu() {
+        // every step along the major axis is covered once in total, split over two pixels
+        let mut canvas = Canvas::new([60; 2], [50; 2]);
+        AngledLine::new(Point::new(5, 5), Point::new(45, 18)).draw_antialiased(&mut canvas);
+        for x in 5..=45 {
+            assert!((coverage(&canvas, (0..50).map(|y| (x, y))) - 1.0).abs() < 0.01);
+        }
+        let shaded = |x, y| ![0, 255].contains(&canvas.get_xy(x, y));
+        assert!((0..50).any(|x| (0..50).any(|y| shaded(x, y))));
+
+        let mut canvas = Canvas::new([60; 2], [50; 2]);
+        AngledLine::new(Point::new(30, 5), Point::new(17, 45)).draw_antialiased(&mut canvas);
+        for y in 5..=45 {
+            assert!((coverage(&canvas, (0..50).map(|x| (x, y))) - 1.0).abs() < 0.01);
+        }
+        assert_eq!(canvas.get_xy(17, 45), 0);
+    }
+
+    /// Pixels of the canvas set to the ink.
+    fn inked(canvas: &Canvas) -> Vec<(i32, i32)> {
+        (0..50)
+            .flat_map(|y| (0..50).map(move |x| (x, y)))
+            .filter(|&(x, y)| canvas.get_xy(x, y) == 0)
+            .collect()
+    }
+
+    #[test]
+    fn horizontal_line() {
+        let mut canvas = Canvas::new([60; 2], [50; 2]);
+        HorizontalLine::new(Point::new(20, 7), 5).draw(&mut canvas);
+        assert_eq!(
+            inked(&canvas),
+            (15..=25).map(|x| (x, 7)).collect::<Vec<_>>()
+        );
+
+        let mut canvas = Canvas::new([60; 2], [50; 2]);
+        HorizontalLine::new(Point::new(20, 7), 5).draw_thick(&mut canvas, 2);
+        assert_eq!(
+            inked(&canvas),
+            (13..=27).map(|x| (x, 7)).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn vertical_line() {
+        let mut canvas = Canvas::new([60; 2], [50; 2]);
+        VerticalLine::new(Point::new(20, 30), 4).draw(&mut canvas);
+        let column: Vec<_> = (26..=34).map(|y| (20, y)).collect();
+        assert_eq!(inked(&canvas), column);
+
+        let mut canvas = Canvas::new([60; 2], [50; 2]);
+        VerticalLine::new(Point::new(20, 30), 4).draw_thick(&mut canvas, 1);
+        assert_eq!(inked(&canvas).len(), 3 * 9);
+        assert!(inked(&canvas).iter().all(|&(x, _)| (19..=21).contains(&x)));
+    }
+
     #[test]
     #[ignore = "visual check"]
     fn angled_line() {
         let aline = AngledLine::new(Point::new(0, 0), Point::new(549, 549));
         let mut img = Canvas::new([600; 2], [550; 2]);
         aline.draw(&mut img);
-        img.save("tests/test.bmp");
+        img.save("tests/test.bmp").unwrap();
     }
 }
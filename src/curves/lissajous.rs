@@ -0,0 +1,109 @@
+use super::{Drawable, Point};
+use crate::canvas::XYDrawable;
+use std::f64::consts::PI;
+
+/// Lissajous figure about a center point, x(t) = Ax sin(a t + phase) and y(t) = Ay sin(b t) for
+/// t in [0, 2π], a closed loop for whole frequencies `a` and `b`. It is drawn by sampling t, as
+/// it folds back on itself in both directions and cannot be marched as a [`super::Curve`].
+pub struct Lissajous {
+    center: Point,
+    x_amplitude: u32,
+    y_amplitude: u32,
+    a: u32,
+    b: u32,
+    phase: f64,
+}
+
+impl Lissajous {
+    pub fn new(
+        center: Point,
+        x_amplitude: u32,
+        y_amplitude: u32,
+        a: u32,
+        b: u32,
+        phase: f64,
+    ) -> Self {
+        Self {
+            center,
+            x_amplitude,
+            y_amplitude,
+            a,
+            b,
+            phase,
+        }
+    }
+
+    /// Point of the figure at parameter `t`.
+    fn at(&self, t: f64) -> (f64, f64) {
+        (
+            self.center.x as f64 + self.x_amplitude as f64 * (self.a as f64 * t + self.phase).sin(),
+            self.center.y as f64 + self.y_amplitude as f64 * (self.b as f64 * t).sin(),
+        )
+    }
+
+    /// Samples along the whole loop. The figure never moves faster than the hypotenuse of its
+    /// speeds along either axis, so twice its length over a period guarantees sub-pixel spacing.
+    fn samples(&self) -> impl Iterator<Item = (f64, f64)> + '_ {
+        let speed = (self.x_amplitude as f64 * self.a as f64)
+            .hypot(self.y_amplitude as f64 * self.b as f64);
+        let n = ((4.0 * PI * speed).ceil() as u32).max(1);
+        (0..=n).map(move |i| self.at(2.0 * PI * i as f64 / n as f64))
+    }
+}
+
+impl Drawable for Lissajous {
+    fn draw(&self, canvas: &mut impl XYDrawable) {
+        let ink = canvas.ink();
+        for (x, y) in self.samples() {
+            canvas.set_xy(x.round() as i32, y.round() as i32, ink);
+        }
+    }
+
+    fn draw_thick(&self, canvas: &mut impl XYDrawable, thickness: u32) {
+        let ink = canvas.ink();
+        for (x, y) in self.samples() {
+            canvas.set_horizontal_line(
+                &Point::new(x.round() as i32, y.round() as i32),
+                ink,
+                thickness,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::canvas::Canvas;
+
+    #[test]
+    fn closed_loop() {
+        for (a, b, phase) in [(1, 1, PI / 2.0), (3, 2, 0.3), (1, 2, 0.0)] {
+            let figure = Lissajous::new(Point::new(50, 50), 30, 20, a, b, phase);
+            let samples = figure.samples().collect::<Vec<_>>();
+            let (first, last) = (samples[0], samples[samples.len() - 1]);
+            assert!((first.0 - last.0).abs() < 1e-9 && (first.1 - last.1).abs() < 1e-9);
+            // consecutive samples touch, so the drawn loop has no gaps
+            assert!(samples
+                .windows(2)
+                .all(|pair| (pair[1].0 - pair[0].0).hypot(pair[1].1 - pair[0].1) <= 1.0));
+        }
+    }
+
+    #[test]
+    fn bounds() {
+        let mut canvas = Canvas::new([120; 2], [100; 2]);
+        Lissajous::new(Point::new(50, 50), 30, 20, 1, 1, PI / 2.0).draw(&mut canvas);
+        // a circle-like ellipse through its four extremes
+        for (x, y) in [(80, 50), (20, 50), (50, 70), (50, 30)] {
+            assert_eq!(canvas.get_xy(x, y), 0, "{} {}", x, y);
+        }
+        assert_eq!(canvas.get_xy(50, 50), 255);
+        for x in 0..100 {
+            for y in 0..100 {
+                let inside = (20..=80).contains(&x) && (30..=70).contains(&y);
+                assert!(inside || canvas.get_xy(x, y) == 255, "({}, {})", x, y);
+            }
+        }
+    }
+}
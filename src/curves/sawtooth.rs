@@ -0,0 +1,106 @@
+use super::{lines::AngledLine, square::riser, Drawable, Gray, Point};
+use crate::canvas::XYDrawable;
+
+/// Entire sawtooth wave: a single ramp up across the whole wavelength, from trough to crest.
+/// Like [`super::sine::Sine`], it starts and ends at its center line, so it drops from there
+/// to the trough at the start and from the crest back to there at the end. These drops are
+/// vertical, which the marcher used for [`super::Curve`] cannot follow, so they are drawn
+/// directly.
+pub struct Sawtooth {
+    start: Point,
+    amplitude: u32,
+    quarter_wavelength: u32,
+}
+
+impl Sawtooth {
+    pub fn new(start: Point, amplitude: u32, quarter_wavelength: u32) -> Self {
+        Self {
+            start,
+            amplitude,
+            quarter_wavelength,
+        }
+    }
+
+    /// Return the ramp, from the trough at the start to the crest at the end.
+    fn ramp(&self) -> AngledLine {
+        let Point { x, y } = self.start;
+        let (a, qw) = (self.amplitude as i32, self.quarter_wavelength as i32);
+        AngledLine::new(Point::new(x, y - a), Point::new(x + 4 * qw, y + a))
+    }
+
+    /// Draw the drops at either end of the ramp.
+    fn drops<D: XYDrawable>(&self, canvas: &mut D, thickness: u32) {
+        let ink = canvas.ink();
+        let Point { x, y } = self.start;
+        let (a, qw) = (self.amplitude as i32, self.quarter_wavelength as i32);
+        riser(canvas, x, y, y - a, ink, thickness);
+        riser(canvas, x + 4 * qw, y + a, y, ink, thickness);
+    }
+}
+
+impl Drawable for Sawtooth {
+    fn draw(&self, canvas: &mut impl XYDrawable) {
+        self.draw_thick(canvas, 0);
+    }
+
+    fn draw_thick(&self, canvas: &mut impl XYDrawable, thickness: u32) {
+        self.ramp().draw_thick(canvas, thickness);
+        self.drops(canvas, thickness);
+    }
+
+    fn draw_antialiased<D: XYDrawable>(&self, canvas: &mut D)
+    where
+        D::Value: Gray,
+    {
+        self.ramp().draw_antialiased(canvas);
+        self.drops(canvas, 0);
+    }
+
+    fn draw_thick_antialiased<D: XYDrawable>(&self, canvas: &mut D, thickness: u32)
+    where
+        D::Value: Gray,
+    {
+        self.ramp().draw_thick_antialiased(canvas, thickness);
+        self.drops(canvas, thickness);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::canvas::Canvas;
+
+    /// Heights set in a column of the canvas.
+    fn column(canvas: &Canvas, x: i32) -> Vec<i32> {
+        (0..100).filter(|&y| canvas.get_xy(x, y) == 0).collect()
+    }
+
+    #[test]
+    fn ramp_and_drops() {
+        let mut canvas = Canvas::new([120; 2], [100; 2]);
+        Sawtooth::new(Point::new(0, 50), 20, 10).draw(&mut canvas);
+
+        // drops at both ends of the wavelength, between the center line and the extremes
+        assert_eq!(column(&canvas, 0), (30..=50).collect::<Vec<_>>());
+        assert_eq!(column(&canvas, 40), (50..=70).collect::<Vec<_>>());
+        assert!(column(&canvas, 41).is_empty());
+
+        // in between, the ramp only goes up
+        let mut previous = 30;
+        for x in 1..40 {
+            let heights = column(&canvas, x);
+            assert!(!heights.is_empty());
+            assert!(heights.iter().all(|&y| y >= previous), "{}", x);
+            previous = *heights.iter().max().unwrap();
+        }
+    }
+
+    #[test]
+    #[ignore = "visual check"]
+    fn sawtooth() {
+        let sawtooth = Sawtooth::new(Point::new(0, 100), 50, 25);
+        let mut img = Canvas::new([240; 2], [200; 2]);
+        sawtooth.draw(&mut img);
+        img.save("tests/sawtooth.bmp").unwrap();
+    }
+}
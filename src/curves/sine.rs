@@ -1,6 +1,6 @@
 use crate::canvas::XYDrawable;
 
-use super::{Curve, Drawable, Point};
+use super::{Curve, Drawable, Gray, Point};
 use num::ToPrimitive;
 use std::f64::consts::PI;
 
@@ -27,6 +27,9 @@ struct QuarterSine {
     quadrant: SineQuadrant,
     amplitude: f64,
     quarter_wavelength: f64,
+    /// sin(x * PI / (2 * quarter_wavelength)) for every column x of the quarter, the cosine
+    /// being the same table read backwards.
+    sines: Vec<f64>,
 }
 
 /// Entire sine wave, this is not implemented as a Curve, but rather drawn as a sum of its
@@ -35,63 +38,282 @@ pub struct Sine {
     start: Point,
     amplitude: u32,
     quarter_wavelength: u32,
+    /// Quadrant the wave starts in.
+    phase: SineQuadrant,
+}
+
+/// Several full periods of a sine wave in a row, drawn one [`Sine`] after the other.
+pub struct SineWave {
+    start: Point,
+    amplitude: u32,
+    quarter_wavelength: u32,
+    num_oscillations: usize,
+    /// Phase of every period, in quarter wavelengths, see [`Sine::with_phase`].
+    phase: u32,
 }
 
 impl Sine {
     pub fn new(start: Point, amplitude: u32, quarter_wavelength: u32) -> Self {
+        Self::with_phase(start, amplitude, quarter_wavelength, 0)
+    }
+
+    /// Sine wave shifted by `phase` quarter wavelengths (modulo 4), e.g. 1 starts at a crest and
+    /// 3 at a trough. The start point is always on the center line, so the first point drawn is
+    /// above or below it for odd phases.
+    pub fn with_phase(start: Point, amplitude: u32, quarter_wavelength: u32, phase: u32) -> Self {
+        let phase = match phase % 4 {
+            0 => SineQuadrant::First,
+            1 => SineQuadrant::Second,
+            2 => SineQuadrant::Third,
+            _ => SineQuadrant::Fourth,
+        };
         Self {
             start,
             amplitude,
             quarter_wavelength,
+            phase,
         }
     }
 
-    /// Return the four comprising quarters.
-    fn quarters(&self) -> [QuarterSine; 4] {
-        let q1 = QuarterSine::new(
-            self.start,
-            SineQuadrant::First,
-            self.amplitude,
-            self.quarter_wavelength,
-        );
-        let q2 = QuarterSine::new(
-            q1.stop,
-            SineQuadrant::Second,
-            self.amplitude,
-            self.quarter_wavelength,
-        );
-        let q3 = QuarterSine::new(
-            q2.stop,
-            SineQuadrant::Third,
-            self.amplitude,
-            self.quarter_wavelength,
+    /// Iterate over the four comprising quarters, starting from the phase quadrant.
+    fn quarters(&self) -> Quarters {
+        Quarters {
+            start: Point::new(
+                self.start.x,
+                self.start.y + self.phase.height(self.amplitude),
+            ),
+            quadrant: self.phase,
+            amplitude: self.amplitude,
+            quarter_wavelength: self.quarter_wavelength,
+            remaining: 4,
+        }
+    }
+
+    /// Where each of the four quarters ends, in drawing order. Starting from the phase quadrant,
+    /// they alternate between the center line and a crest or trough, `quarter_wavelength`
+    /// pixels apart, e.g. for no phase `(qw, a)`, `(2qw, 0)`, `(3qw, -a)` then `(4qw, 0)` from
+    /// the start.
+    pub fn quarter_stops(&self) -> [Point; 4] {
+        let mut start = Point::new(
+            self.start.x,
+            self.start.y + self.phase.height(self.amplitude),
         );
-        let q4 = QuarterSine::new(
-            q3.stop,
-            SineQuadrant::Fourth,
+        let mut quadrant = self.phase;
+        [(); 4].map(|_| {
+            start = quadrant.stop(&start, self.quarter_wavelength, self.amplitude);
+            quadrant = quadrant.next();
+            start
+        })
+    }
+
+    /// End of the wave, a whole wavelength to the right of where it starts and at the same
+    /// height, where the next period of a [`SineWave`] picks up.
+    pub fn stop(&self) -> Point {
+        self.quarter_stops()[3]
+    }
+
+    /// Pixels traced by the wave, quarter after quarter, see [`Curve::points`].
+    pub fn points(&self) -> Vec<Point> {
+        self.quarters()
+            .flat_map(|quarter| quarter.points())
+            .collect()
+    }
+
+    /// Draw with a thickness varying along the wave, from `min_thickness` at the crests and
+    /// troughs to `max_thickness` where it crosses the center line, see
+    /// [`Drawable::draw_thick`]. Swap them for strokes that are thickest at the crests.
+    pub fn draw_thick_variable(
+        &self,
+        canvas: &mut impl XYDrawable,
+        min_thickness: u32,
+        max_thickness: u32,
+    ) {
+        let ink = canvas.ink();
+        let (min_t, max_t) = (min_thickness as f64, max_thickness as f64);
+        for quarter in self.quarters() {
+            for point in quarter.points() {
+                let steepness = quarter.phase(point.x).cos().abs();
+                let thickness = (min_t + (max_t - min_t) * steepness).round() as u32;
+                canvas.set_horizontal_line(&point, ink, thickness);
+            }
+        }
+    }
+
+    /// Cubic Bézier approximation of the wave, one segment per quarter. Each segment is given as
+    /// its two control points followed by its end point, the start point being the end of the
+    /// previous segment (or the start of the sine).
+    pub fn cubic_beziers(&self) -> [[(f64, f64); 3]; 4] {
+        let mut quarters = self.quarters().map(|quarter| quarter.cubic_bezier());
+        [(); 4].map(|_| quarters.next().expect("a sine has four quarters"))
+    }
+}
+
+impl SineWave {
+    pub fn new(
+        start: Point,
+        amplitude: u32,
+        quarter_wavelength: u32,
+        num_oscillations: usize,
+    ) -> Self {
+        Self::with_phase(start, amplitude, quarter_wavelength, num_oscillations, 0)
+    }
+
+    /// Wave whose periods are all shifted by `phase` quarter wavelengths, see
+    /// [`Sine::with_phase`].
+    pub fn with_phase(
+        start: Point,
+        amplitude: u32,
+        quarter_wavelength: u32,
+        num_oscillations: usize,
+        phase: u32,
+    ) -> Self {
+        Self {
+            start,
+            amplitude,
+            quarter_wavelength,
+            num_oscillations,
+            phase,
+        }
+    }
+
+    /// Every period of the wave, each starting where the previous one stopped.
+    fn periods(&self) -> impl Iterator<Item = Sine> + '_ {
+        let wavelength = 4 * self.quarter_wavelength as i32;
+        (0..self.num_oscillations as i32).map(move |i| {
+            let start = self.start + Point::new(i * wavelength, 0);
+            Sine::with_phase(start, self.amplitude, self.quarter_wavelength, self.phase)
+        })
+    }
+
+    /// Pixels traced by the wave, period after period.
+    pub fn points(&self) -> Vec<Point> {
+        self.periods().flat_map(|sine| sine.points()).collect()
+    }
+}
+
+impl Drawable for SineWave {
+    fn draw(&self, canvas: &mut impl XYDrawable) {
+        for sine in self.periods() {
+            sine.draw(canvas);
+        }
+    }
+
+    fn draw_thick(&self, canvas: &mut impl XYDrawable, thickness: u32) {
+        for sine in self.periods() {
+            sine.draw_thick(canvas, thickness);
+        }
+    }
+
+    fn draw_antialiased<D: XYDrawable>(&self, canvas: &mut D)
+    where
+        D::Value: Gray,
+    {
+        for sine in self.periods() {
+            sine.draw_antialiased(canvas);
+        }
+    }
+
+    fn draw_thick_antialiased<D: XYDrawable>(&self, canvas: &mut D, thickness: u32)
+    where
+        D::Value: Gray,
+    {
+        for sine in self.periods() {
+            sine.draw_thick_antialiased(canvas, thickness);
+        }
+    }
+}
+
+/// Iterator over the quarters of a [`Sine`], each one starting where the previous one stopped.
+struct Quarters {
+    start: Point,
+    quadrant: SineQuadrant,
+    amplitude: u32,
+    quarter_wavelength: u32,
+    remaining: u8,
+}
+
+impl Iterator for Quarters {
+    type Item = QuarterSine;
+
+    fn next(&mut self) -> Option<QuarterSine> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let quarter = QuarterSine::new(
+            self.start,
+            self.quadrant,
             self.amplitude,
             self.quarter_wavelength,
         );
-        // QUESTION: perhaps turn into iterator, avoid some duplication?
-        [q1, q2, q3, q4]
+        self.start = quarter.stop;
+        self.quadrant = self.quadrant.next();
+        self.remaining -= 1;
+        Some(quarter)
     }
 }
 
 impl Drawable for Sine {
     fn draw(&self, canvas: &mut impl XYDrawable) {
-        for quarter in self.quarters().iter() {
+        for quarter in self.quarters() {
             quarter.draw(canvas);
         }
     }
 
     fn draw_thick(&self, canvas: &mut impl XYDrawable, thickness: u32) {
-        for quarter in self.quarters().iter() {
+        for quarter in self.quarters() {
             quarter.draw_thick(canvas, thickness);
         }
     }
+
+    fn draw_antialiased<D: XYDrawable>(&self, canvas: &mut D)
+    where
+        D::Value: Gray,
+    {
+        for quarter in self.quarters() {
+            quarter.draw_antialiased(canvas);
+        }
+    }
+
+    fn draw_thick_antialiased<D: XYDrawable>(&self, canvas: &mut D, thickness: u32)
+    where
+        D::Value: Gray,
+    {
+        for quarter in self.quarters() {
+            quarter.draw_thick_antialiased(canvas, thickness);
+        }
+    }
 }
 
 impl SineQuadrant {
+    /// Quadrant following this one.
+    fn next(&self) -> Self {
+        match self {
+            SineQuadrant::First => SineQuadrant::Second,
+            SineQuadrant::Second => SineQuadrant::Third,
+            SineQuadrant::Third => SineQuadrant::Fourth,
+            SineQuadrant::Fourth => SineQuadrant::First,
+        }
+    }
+
+    /// Height of the start of the quadrant above the center line.
+    fn height(&self, amplitude: u32) -> i32 {
+        match self {
+            SineQuadrant::First | SineQuadrant::Third => 0,
+            SineQuadrant::Second => amplitude as i32,
+            SineQuadrant::Fourth => -(amplitude as i32),
+        }
+    }
+
+    /// Slopes at the start and stop of the quadrant, for unit amplitude and quarter wavelength.
+    fn slopes(&self) -> (f64, f64) {
+        match self {
+            SineQuadrant::First => (PI / 2.0, 0.0),
+            SineQuadrant::Second => (0.0, -PI / 2.0),
+            SineQuadrant::Third => (-PI / 2.0, 0.0),
+            SineQuadrant::Fourth => (0.0, PI / 2.0),
+        }
+    }
+
     fn stop(&self, start: &Point, quarter_wavelength: u32, amplitude: u32) -> Point {
         let dy = match self {
             SineQuadrant::First => amplitude as i32,
@@ -100,7 +322,7 @@ impl SineQuadrant {
             SineQuadrant::Fourth => amplitude as i32,
         };
 
-        Point::new(start.x + quarter_wavelength, (start.y as i32 + dy) as u32)
+        Point::new(start.x + quarter_wavelength as i32, start.y + dy)
     }
 }
 
@@ -108,34 +330,71 @@ impl QuarterSine {
     /// Creates a new quarter sine, with cached stop point and amplitude and quarter wavelength pre-converted to f64.
     fn new(start: Point, quadrant: SineQuadrant, amplitude: u32, quarter_wavelength: u32) -> Self {
         let stop = quadrant.stop(&start, quarter_wavelength, amplitude);
+        let qw = quarter_wavelength as f64;
+        let sines = (0..=quarter_wavelength)
+            .map(|x| (x as f64 * PI / (2.0 * qw)).sin())
+            .collect();
 
         Self {
             start,
             quadrant,
             amplitude: amplitude as f64,
-            quarter_wavelength: quarter_wavelength as f64,
+            quarter_wavelength: qw,
             stop,
+            sines,
+        }
+    }
+
+    /// sin and cos of x * PI / (2 * quarter_wavelength), looked up in the table for columns
+    /// inside the quarter and computed otherwise.
+    fn sin_cos(&self, x: i32) -> (f64, f64) {
+        let last = self.sines.len() - 1;
+        match usize::try_from(x) {
+            Ok(i) if i <= last => (self.sines[i], self.sines[last - i]),
+            _ => (x as f64 * PI / (2.0 * self.quarter_wavelength)).sin_cos(),
         }
     }
 
+    /// Phase of the wave at column `x`, between 0 and 2 PI, e.g. PI / 2 at the crest.
+    fn phase(&self, x: i32) -> f64 {
+        let quadrant = match self.quadrant {
+            SineQuadrant::First => 0.0,
+            SineQuadrant::Second => 1.0,
+            SineQuadrant::Third => 2.0,
+            SineQuadrant::Fourth => 3.0,
+        };
+        let fraction = if self.quarter_wavelength == 0.0 {
+            0.0
+        } else {
+            (x - self.start.x) as f64 / self.quarter_wavelength
+        };
+        (quadrant + fraction) * PI / 2.0
+    }
+
+    /// Cubic Bézier segment matching the quarter's end points and the slopes at those points.
+    fn cubic_bezier(&self) -> [(f64, f64); 3] {
+        let (s0, s1) = self.quadrant.slopes();
+        let (x0, y0) = (self.start.x as f64, self.start.y as f64);
+        let (x3, y3) = (self.stop.x as f64, self.stop.y as f64);
+        let dx = self.quarter_wavelength / 3.0;
+        let dy = self.amplitude / 3.0;
+        [(x0 + dx, y0 + s0 * dy), (x3 - dx, y3 - s1 * dy), (x3, y3)]
+    }
+
     /// Auxiliary equation for centering start of quadrant equation at current point.
     fn equation_aux(&self, x: i32, y: i32) -> f64 {
-        let x = x.to_f64().expect("could not convert to f64");
-        let y = y.to_f64().expect("could not convert to f64");
+        // a quarter with no width is the limit of a steep one, a vertical segment
+        if self.quarter_wavelength == 0.0 {
+            return x.to_f64().expect("could not convert to f64");
+        }
 
+        let (sin, cos) = self.sin_cos(x);
+        let y = y.to_f64().expect("could not convert to f64");
         match self.quadrant {
-            SineQuadrant::First => {
-                y - self.amplitude * (x * PI / (2.0 * self.quarter_wavelength)).sin()
-            }
-            SineQuadrant::Second => {
-                y - self.amplitude * ((x * PI / (2.0 * self.quarter_wavelength)).cos() - 1.0)
-            }
-            SineQuadrant::Third => {
-                y + self.amplitude * (x * PI / (2.0 * self.quarter_wavelength)).sin()
-            }
-            SineQuadrant::Fourth => {
-                y + self.amplitude * ((x * PI / (2.0 * self.quarter_wavelength)).cos() - 1.0)
-            }
+            SineQuadrant::First => y - self.amplitude * sin,
+            SineQuadrant::Second => y - self.amplitude * (cos - 1.0),
+            SineQuadrant::Third => y + self.amplitude * sin,
+            SineQuadrant::Fourth => y + self.amplitude * (cos - 1.0),
         }
     }
 }
@@ -152,10 +411,8 @@ impl Curve for QuarterSine {
     }
 
     fn equation(&self, point: &Point) -> Self::T {
-        self.equation_aux(
-            point.x as i32 - self.start.x as i32,
-            point.y as i32 - self.start.y as i32,
-        )
+        let d = *point - self.start;
+        self.equation_aux(d.x, d.y)
     }
 }
 
@@ -164,15 +421,237 @@ mod tests {
     use super::*;
     use crate::{
         canvas::{Canvas, XYDrawable},
-        curves::Drawable,
+        curves::{Curve, Drawable},
     };
 
+    #[test]
+    fn cubic_beziers() {
+        let beziers = Sine::new(Point::new(0, 100), 30, 10).cubic_beziers();
+        let ends: Vec<_> = beziers.iter().map(|b| b[2]).collect();
+        assert_eq!(
+            ends,
+            vec![(10.0, 130.0), (20.0, 100.0), (30.0, 70.0), (40.0, 100.0)]
+        );
+        // the crest is flat, so the control points next to it are level with it
+        assert_eq!(beziers[0][1].1, 130.0);
+        assert_eq!(beziers[1][0].1, 130.0);
+    }
+
+    #[test]
+    fn sine_table() {
+        for quarter in Sine::new(Point::new(0, 100), 30, 17).quarters() {
+            let qw = quarter.quarter_wavelength;
+            for x in -3..21 {
+                let angle = x as f64 * PI / (2.0 * qw);
+                for y in -40..40 {
+                    let direct = match quarter.quadrant {
+                        SineQuadrant::First => y as f64 - 30.0 * angle.sin(),
+                        SineQuadrant::Second => y as f64 - 30.0 * (angle.cos() - 1.0),
+                        SineQuadrant::Third => y as f64 + 30.0 * angle.sin(),
+                        SineQuadrant::Fourth => y as f64 + 30.0 * (angle.cos() - 1.0),
+                    };
+                    assert!((quarter.equation_aux(x, y) - direct).abs() < 1e-9);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn quarter_stops() {
+        let sine = Sine::new(Point::new(5, 100), 30, 10);
+        let offsets = sine.quarter_stops().map(|p| (p.x - 5, p.y - 100));
+        assert_eq!(offsets, [(10, 30), (20, 0), (30, -30), (40, 0)]);
+        assert_eq!(sine.stop(), Point::new(45, 100));
+
+        // phased waves start and stop off the center line, at the same height
+        let heights = |phase| {
+            let sine = Sine::with_phase(Point::new(0, 100), 30, 10, phase);
+            sine.quarter_stops().map(|p| p.y - 100)
+        };
+        assert_eq!(heights(1), [0, -30, 0, 30]);
+        assert_eq!(heights(2), [-30, 0, 30, 0]);
+        assert_eq!(heights(3), [0, 30, 0, -30]);
+        for phase in 0..4 {
+            let sine = Sine::with_phase(Point::new(0, 100), 30, 10, phase);
+            assert_eq!(sine.stop().x, 40);
+            assert_eq!(
+                sine.quarter_stops(),
+                sine.quarters().map(|q| q.stop).collect::<Vec<_>>()[..]
+            );
+        }
+
+        // the marcher lands on every stop, however steep or flat the quarters
+        for (a, qw) in [
+            (0, 10),
+            (1, 10),
+            (10, 10),
+            (37, 3),
+            (120, 2),
+            (5, 1),
+            (3, 0),
+        ] {
+            let sine = Sine::new(Point::new(0, 150), a, qw);
+            for (quarter, stop) in sine.quarters().zip(sine.quarter_stops()) {
+                assert_eq!(
+                    quarter.points().last(),
+                    Some(&stop),
+                    "a = {}, qw = {}",
+                    a,
+                    qw
+                );
+            }
+            assert_eq!(sine.points().last(), Some(&Point::new(4 * qw as i32, 150)));
+        }
+
+        // periods of a wave follow on from each other without drifting
+        let periods: Vec<_> = SineWave::new(Point::new(0, 50), 20, 7, 5)
+            .periods()
+            .collect();
+        assert!(periods
+            .windows(2)
+            .all(|pair| pair[0].stop() == pair[1].start));
+        assert_eq!(periods[4].stop(), Point::new(5 * 28, 50));
+    }
+
+    #[test]
+    fn quarters() {
+        let quarters: Vec<_> = Sine::new(Point::new(0, 100), 30, 10).quarters().collect();
+        assert_eq!(quarters.len(), 4);
+        assert_eq!(quarters[0].start, Point::new(0, 100));
+        assert!(quarters
+            .windows(2)
+            .all(|pair| pair[0].stop == pair[1].start));
+        let stops: Vec<_> = quarters.iter().map(|quarter| quarter.stop).collect();
+        assert_eq!(
+            stops,
+            vec![
+                Point::new(10, 130),
+                Point::new(20, 100),
+                Point::new(30, 70),
+                Point::new(40, 100)
+            ]
+        );
+    }
+
+    #[test]
+    fn phase() {
+        // columns where the curve touches its first crest, checked to be around `expected`
+        let crest = |sine: Sine, expected: i32| {
+            let mut canvas = Canvas::new([120; 2], [100; 2]);
+            sine.draw(&mut canvas);
+            let columns: Vec<_> = (0..36).filter(|&x| canvas.get_xy(x, 80) == 0).collect();
+            assert!(columns.contains(&expected), "{:?}", columns);
+            assert!(
+                columns.iter().all(|x| (x - expected).abs() <= 2),
+                "{:?}",
+                columns
+            );
+        };
+        crest(Sine::new(Point::new(0, 50), 30, 10), 10);
+        crest(Sine::with_phase(Point::new(0, 50), 30, 10, 1), 0);
+        crest(Sine::with_phase(Point::new(0, 50), 30, 10, 2), 30);
+        crest(Sine::with_phase(Point::new(0, 50), 30, 10, 4), 10);
+
+        let quarters: Vec<_> = Sine::with_phase(Point::new(0, 50), 30, 10, 3)
+            .quarters()
+            .collect();
+        assert_eq!(quarters[0].start, Point::new(0, 20));
+        assert_eq!(quarters[3].stop, Point::new(40, 20));
+    }
+
+    #[test]
+    fn steep() {
+        // the marcher may step vertically, so even near-vertical quarters stay connected
+        let sine = Sine::new(Point::new(0, 150), 120, 2);
+        for quarter in sine.quarters() {
+            let points = quarter.points();
+            assert_eq!(points.last(), Some(&quarter.stop));
+            assert!(points.windows(2).all(
+                |pair| (pair[0].x - pair[1].x).abs() <= 1 && (pair[0].y - pair[1].y).abs() <= 1
+            ));
+        }
+
+        let mut canvas = Canvas::new([320; 2], [300; 2]);
+        sine.draw(&mut canvas);
+        assert!((30..=270).all(|y| (0..=8).any(|x| canvas.get_xy(x, y) == 0)));
+
+        let mut canvas = Canvas::new([320; 2], [300; 2]);
+        Sine::new(Point::new(5, 150), 120, 0).draw(&mut canvas);
+        assert!((30..=270).all(|y| canvas.get_xy(5, y) == 0));
+    }
+
+    #[test]
+    fn thick_antialiased() {
+        let sine = Sine::new(Point::new(0, 100), 60, 10);
+        let mut canvas = Canvas::new([220; 2], [200; 2]);
+        sine.draw_thick_antialiased(&mut canvas, 1);
+
+        // across the steep middle of the wave, going down, between pixel centers
+        let row: Vec<_> = (10..=30)
+            .map(|x| canvas.get_xy(x, 110))
+            .filter(|&v| v < 255)
+            .collect();
+        assert!(row.len() > 3, "{:?}", row);
+        assert_eq!(row[row.len() / 2], 0);
+        assert!(row[0] > 0 && row[row.len() - 1] > 0, "{:?}", row);
+        assert!(canvas.image().pixels().any(|p| p.0[0] > 0 && p.0[0] < 255));
+    }
+
+    #[test]
+    fn variable_thickness() {
+        // number of set pixels on a row
+        let width =
+            |canvas: &Canvas, y: i32| (0..100).filter(|&x| canvas.get_xy(x, y) == 0).count();
+        let sine = Sine::new(Point::new(20, 50), 30, 20);
+
+        let mut crossings = Canvas::new([120; 2], [100; 2]);
+        sine.draw_thick_variable(&mut crossings, 0, 6);
+        let mut crests = Canvas::new([120; 2], [100; 2]);
+        sine.draw_thick_variable(&mut crests, 6, 0);
+
+        assert!(width(&crossings, 50) > width(&crests, 50));
+        assert!(width(&crossings, 80) < width(&crests, 80));
+        assert!(width(&crossings, 20) < width(&crests, 20));
+    }
+
+    #[test]
+    #[ignore = "visual check"]
+    fn sine_variable() {
+        let sinewave = Sine::new(Point::new(0, 100), 50, 40);
+        let mut img = Canvas::new([600; 2], [400; 2]);
+        sinewave.draw_thick_variable(&mut img, 1, 8);
+        img.save("tests/sine_variable.bmp").unwrap();
+    }
+
+    #[test]
+    fn sine_wave() {
+        let wave = SineWave::new(Point::new(0, 50), 20, 5, 3);
+        let points = wave.points();
+        assert_eq!(points.first(), Some(&Point::new(0, 50)));
+        assert_eq!(points.last(), Some(&Point::new(60, 50)));
+        assert!(points.contains(&Point::new(25, 70)));
+        assert!(points.contains(&Point::new(55, 30)));
+        assert!(SineWave::new(Point::new(0, 50), 20, 5, 0)
+            .points()
+            .is_empty());
+    }
+
+    #[test]
+    fn below_axis() {
+        let mut canvas = Canvas::new([120; 2], [100; 2]);
+        Sine::new(Point::new(0, 5), 20, 10).draw_thick(&mut canvas, 2);
+
+        // the crest is drawn, the trough is dropped instead of wrapping around
+        assert_eq!(canvas.get_xy(10, 25), 0);
+        assert!((0..100).all(|x| (30..100).all(|y| canvas.get_xy(x, y) == 255)));
+    }
+
     #[test]
     #[ignore = "visual check"]
     fn sine() {
         let sinewave = Sine::new(Point::new(0, 100), 50, 10);
         let mut img = Canvas::new([600; 2], [400; 2]);
         sinewave.draw(&mut img);
-        img.save("test.bmp");
+        img.save("test.bmp").unwrap();
     }
 }
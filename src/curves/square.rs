@@ -0,0 +1,93 @@
+use super::{Drawable, Point};
+use crate::canvas::XYDrawable;
+
+/// Entire square wave: high for the first half of the wavelength and low for the second, with
+/// vertical risers in between. Like [`super::sine::Sine`], it starts and ends at its center line.
+///
+/// The risers cannot be followed by the marcher used for [`super::Curve`], so the wave is drawn
+/// directly with horizontal and vertical lines.
+pub struct Square {
+    start: Point,
+    amplitude: u32,
+    quarter_wavelength: u32,
+}
+
+impl Square {
+    pub fn new(start: Point, amplitude: u32, quarter_wavelength: u32) -> Self {
+        Self {
+            start,
+            amplitude,
+            quarter_wavelength,
+        }
+    }
+}
+
+/// Draw a vertical line between two heights, inclusive, expanded horizontally by `thickness`.
+pub(super) fn riser<D: XYDrawable>(
+    canvas: &mut D,
+    x: i32,
+    y0: i32,
+    y1: i32,
+    value: D::Value,
+    thickness: u32,
+) {
+    let (lo, hi) = if y0 < y1 { (y0, y1) } else { (y1, y0) };
+    for x in x - thickness as i32..=x + thickness as i32 {
+        canvas.set_vertical_line(&Point::new(x, (lo + hi) / 2), value, (hi - lo) as u32 / 2);
+        canvas.set_xy(x, lo, value);
+        canvas.set_xy(x, hi, value);
+    }
+}
+
+impl Drawable for Square {
+    fn draw(&self, canvas: &mut impl XYDrawable) {
+        self.draw_thick(canvas, 0);
+    }
+
+    fn draw_thick(&self, canvas: &mut impl XYDrawable, thickness: u32) {
+        let ink = canvas.ink();
+        let Point { x, y } = self.start;
+        let (a, qw) = (self.amplitude as i32, self.quarter_wavelength as i32);
+
+        riser(canvas, x, y, y + a, ink, thickness);
+        canvas.set_horizontal_line(&Point::new(x + qw, y + a), ink, qw as u32 + thickness);
+        riser(canvas, x + 2 * qw, y + a, y - a, ink, thickness);
+        canvas.set_horizontal_line(&Point::new(x + 3 * qw, y - a), ink, qw as u32 + thickness);
+        riser(canvas, x + 4 * qw, y - a, y, ink, thickness);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::canvas::Canvas;
+
+    #[test]
+    fn tops_and_risers() {
+        let mut canvas = Canvas::new([120; 2], [100; 2]);
+        Square::new(Point::new(0, 50), 10, 5).draw(&mut canvas);
+
+        let mut expected = vec![];
+        expected.extend((50..=60).map(|y| (0, y)));
+        expected.extend((1..=10).map(|x| (x, 60)));
+        expected.extend((40..60).map(|y| (10, y)));
+        expected.extend((11..=20).map(|x| (x, 40)));
+        expected.extend((41..=50).map(|y| (20, y)));
+
+        for x in 0..100 {
+            for y in 0..100 {
+                let set = canvas.get_xy(x, y) == 0;
+                assert_eq!(set, expected.contains(&(x, y)), "({}, {})", x, y);
+            }
+        }
+    }
+
+    #[test]
+    #[ignore = "visual check"]
+    fn square() {
+        let square = Square::new(Point::new(0, 100), 50, 25);
+        let mut img = Canvas::new([240; 2], [200; 2]);
+        square.draw(&mut img);
+        img.save("tests/square.bmp").unwrap();
+    }
+}
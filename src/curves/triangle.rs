@@ -0,0 +1,90 @@
+use super::{lines::AngledLine, Drawable, Gray, Point};
+use crate::canvas::XYDrawable;
+
+/// Entire triangle wave, made up of four straight ramps with sharp peaks in between. Like
+/// [`super::sine::Sine`], it starts at a zero crossing going up.
+pub struct Triangle {
+    start: Point,
+    amplitude: u32,
+    quarter_wavelength: u32,
+}
+
+impl Triangle {
+    pub fn new(start: Point, amplitude: u32, quarter_wavelength: u32) -> Self {
+        Self {
+            start,
+            amplitude,
+            quarter_wavelength,
+        }
+    }
+
+    /// Return the four comprising ramps.
+    fn ramps(&self) -> [AngledLine; 4] {
+        let Point { x, y } = self.start;
+        let (a, qw) = (self.amplitude as i32, self.quarter_wavelength as i32);
+        let points = [
+            self.start,
+            Point::new(x + qw, y + a),
+            Point::new(x + 2 * qw, y),
+            Point::new(x + 3 * qw, y - a),
+            Point::new(x + 4 * qw, y),
+        ];
+        [0, 1, 2, 3].map(|i| AngledLine::new(points[i], points[i + 1]))
+    }
+}
+
+impl Drawable for Triangle {
+    fn draw(&self, canvas: &mut impl XYDrawable) {
+        for ramp in self.ramps().iter() {
+            ramp.draw(canvas);
+        }
+    }
+
+    fn draw_thick(&self, canvas: &mut impl XYDrawable, thickness: u32) {
+        for ramp in self.ramps().iter() {
+            ramp.draw_thick(canvas, thickness);
+        }
+    }
+
+    fn draw_antialiased<D: XYDrawable>(&self, canvas: &mut D)
+    where
+        D::Value: Gray,
+    {
+        for ramp in self.ramps().iter() {
+            ramp.draw_antialiased(canvas);
+        }
+    }
+
+    fn draw_thick_antialiased<D: XYDrawable>(&self, canvas: &mut D, thickness: u32)
+    where
+        D::Value: Gray,
+    {
+        for ramp in self.ramps().iter() {
+            ramp.draw_thick_antialiased(canvas, thickness);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::canvas::Canvas;
+
+    #[test]
+    fn peaks() {
+        let ramps = Triangle::new(Point::new(0, 100), 20, 10).ramps();
+        assert_eq!(ramps[0].stop, Point::new(10, 120));
+        assert_eq!(ramps[1].stop, Point::new(20, 100));
+        assert_eq!(ramps[2].stop, Point::new(30, 80));
+        assert_eq!(ramps[3].stop, Point::new(40, 100));
+    }
+
+    #[test]
+    #[ignore = "visual check"]
+    fn triangle() {
+        let triangle = Triangle::new(Point::new(0, 100), 50, 25);
+        let mut img = Canvas::new([240; 2], [200; 2]);
+        triangle.draw(&mut img);
+        img.save("tests/triangle.bmp").unwrap();
+    }
+}
@@ -0,0 +1,12 @@
+pub mod canvas;
+pub mod curves;
+pub mod plotter;
+pub mod prelude;
+
+pub use canvas::{Canvas, XYDrawable};
+pub use curves::{
+    antialiased_value,
+    sine::{Sine, SineWave},
+    Curve, Drawable, Gray, Point,
+};
+pub use plotter::{Plotter, PlotterBuilder, PlotterSettings};
@@ -1,10 +1,5 @@
-mod canvas;
-mod curves;
-mod plotter;
-
-use canvas::XYDrawable;
-use clap::Parser;
-use plotter::Plotter;
+use clap::{CommandFactory, ErrorKind, Parser};
+use sineart::{PlotterBuilder, XYDrawable};
 use std::path::PathBuf;
 
 /// Plots images as sinewave art, inspired by /u/tfoust10's Reddit posts.
@@ -12,16 +7,29 @@ use std::path::PathBuf;
 #[clap(author, version, about, long_about = None)]
 struct Args {
     /// Source image.
-    input: PathBuf,
+    #[clap(short = 'i', long = "source")]
+    source: PathBuf,
+
+    /// Number of sine oscillations per row.
+    #[clap(
+        short = 'c',
+        long = "width",
+        value_name = "WIDTH",
+        visible_alias = "cols",
+        default_value = "50"
+    )]
+    hcells: u32,
 
     /// Number of rows of sine waves.
-    #[clap(short = 'r', long = "rows", default_value = "50")]
+    #[clap(
+        short = 'r',
+        long = "height",
+        value_name = "HEIGHT",
+        visible_alias = "rows",
+        default_value = "50"
+    )]
     vcells: u32,
 
-    /// Number of sine oscillations.
-    #[clap(short = 'c', long = "cols", default_value = "50")]
-    hcells: u32,
-
     /// Percentage scaling of image resolution.
     #[clap(short = 's', long = "scale", default_value = "100")]
     scale: u32,
@@ -34,30 +42,107 @@ struct Args {
     #[clap(long = "threshold", default_value = "200")]
     threshold: u8,
 
-    /// Output image path. Defaults to $INPUT_sine.jpg.
+    /// Output image path. Defaults to $SOURCE_sine.jpg.
     #[clap(short = 'o', long = "output")]
     output: Option<PathBuf>,
 }
 
+impl Args {
+    /// Check the arguments before any image processing is done.
+    fn validate(&self) -> Result<(), String> {
+        if !self.source.is_file() {
+            return Err(format!(
+                "source image {} does not exist",
+                self.source.display()
+            ));
+        }
+        if self.hcells == 0 || self.vcells == 0 {
+            return Err("width and height must be non-zero".to_string());
+        }
+        if self.scale == 0 {
+            return Err("scale must be non-zero".to_string());
+        }
+        Ok(())
+    }
+
+    /// Output path, either as given or derived from the source file name.
+    fn output(&self) -> PathBuf {
+        self.output.clone().unwrap_or_else(|| {
+            self.source.with_file_name(format!(
+                "{}_sine.jpg",
+                self.source
+                    .file_stem()
+                    .expect("could not read source file stem")
+                    .to_string_lossy()
+            ))
+        })
+    }
+}
+
 fn main() {
     let args = Args::parse();
-    let output = args.output.unwrap_or_else(|| {
-        args.input.with_file_name(format!(
-            "{}_sine.jpg",
-            args.input
-                .file_stem()
-                .expect("could not read input file stem")
-                .to_str()
-                .expect("input file stem is not valid unicode")
-        ))
-    });
-    let mut plotter = Plotter::new(
-        args.hcells,
-        args.vcells,
-        args.input,
-        args.scale,
-        args.threshold,
-    );
-    plotter.draw(args.thickness);
-    plotter.canvas.save(output);
+    if let Err(message) = args.validate() {
+        Args::command()
+            .error(ErrorKind::ValueValidation, message)
+            .exit();
+    }
+
+    let output = args.output();
+    let mut plotter = match PlotterBuilder::new()
+        .source(&args.source)
+        .cells(args.hcells, args.vcells)
+        .scale(args.scale)
+        .threshold(args.threshold)
+        .thickness(args.thickness)
+        .build()
+    {
+        Ok(plotter) => plotter,
+        Err(e) => Args::command().error(ErrorKind::Io, e).exit(),
+    };
+    plotter.render();
+    if let Err(e) = plotter.canvas.save(&output) {
+        Args::command()
+            .error(
+                ErrorKind::Io,
+                format!("could not save {}: {}", output.display(), e),
+            )
+            .exit();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_long_flags() {
+        let args = Args::try_parse_from([
+            "sineart", "--source", "in.jpg", "--width", "30", "--height", "20", "--output",
+            "out.png",
+        ])
+        .unwrap();
+        assert_eq!(args.source, PathBuf::from("in.jpg"));
+        assert_eq!(args.hcells, 30);
+        assert_eq!(args.vcells, 20);
+        assert_eq!(args.output(), PathBuf::from("out.png"));
+    }
+
+    #[test]
+    fn default_output() {
+        let args = Args::try_parse_from(["sineart", "--source", "images/in.jpg"]).unwrap();
+        assert_eq!(args.output(), PathBuf::from("images/in_sine.jpg"));
+    }
+
+    #[test]
+    fn validate_missing_source() {
+        let args = Args::try_parse_from(["sineart", "--source", "does/not/exist.jpg"]).unwrap();
+        assert!(args.validate().is_err());
+    }
+
+    #[test]
+    fn validate_zero_cells() {
+        let args =
+            Args::try_parse_from(["sineart", "--source", "Cargo.toml", "--width", "0"]).unwrap();
+        assert!(args.validate().is_err());
+    }
 }
@@ -1,104 +1,4126 @@
 use crate::{
-    canvas::Canvas,
-    curves::{sine::Sine, Drawable, Point},
+    canvas::{write_image, Canvas, RgbCanvas, Translated, Transposed, XYDrawable},
+    curves::{
+        lines::{HorizontalLine, VerticalLine},
+        lissajous::Lissajous,
+        sawtooth::Sawtooth,
+        sine::{Sine, SineWave},
+        square::Square,
+        triangle::Triangle,
+        Drawable, Gray, Inked, Point,
+    },
 };
-use image::{imageops::FilterType, io::Reader as ImageReader, GrayImage};
-use std::{cmp::min, path::Path};
+#[cfg(feature = "gif")]
+use image::{
+    codecs::gif::{GifEncoder, Repeat},
+    Delay, Frame,
+};
+use image::{
+    imageops::{self, FilterType},
+    io::Reader as ImageReader,
+    DynamicImage, GrayImage, ImageError, ImageFormat, ImageResult, Rgb, RgbImage,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::{
+    cmp::{max, min},
+    collections::BTreeMap,
+    f64::consts::PI,
+    fmt::{self, Write},
+    io,
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// Odd constant mixed into the jitter seed of every row of cells, see [`Plotter::set_jitter`].
+const ROW_SEED: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// Shortest quarter wavelength used when encoding brightness as frequency.
+const MIN_FM_QUARTER_WAVELENGTH: u32 = 2;
+
+/// Supersampling factor used to antialias [`Plotter::draw_duotone`].
+const DUOTONE_SUPERSAMPLING: u32 = 4;
+
+/// Smallest cell width or height in pixels, below which waves would be flattened to nothing.
+const MIN_CELL_SIZE: u32 = 4;
+
+/// Save frames, e.g. from [`Plotter::render_frames`], as a looping animated GIF showing each
+/// frame for `delay_ms` milliseconds. Requires the `gif` feature.
+#[cfg(feature = "gif")]
+pub fn save_gif<P: AsRef<Path>>(frames: &[GrayImage], path: P, delay_ms: u32) -> ImageResult<()> {
+    let mut encoder = GifEncoder::new(std::fs::File::create(path)?);
+    encoder.set_repeat(Repeat::Infinite)?;
+    encoder.encode_frames(frames.iter().map(|frame| {
+        Frame::from_parts(
+            DynamicImage::ImageLuma8(frame.clone()).into_rgba8(),
+            0,
+            0,
+            Delay::from_numer_denom_ms(delay_ms, 1),
+        )
+    }))
+}
+
+/// Outcome of [`process_dir`] for every file of the input directory.
+#[derive(Debug, Default)]
+pub struct BatchReport {
+    /// Images written, as (source, output) paths.
+    pub rendered: Vec<(PathBuf, PathBuf)>,
+    /// Files that are not images, judging by their extension.
+    pub skipped: Vec<PathBuf>,
+    /// Images that could not be rendered or saved, and why.
+    pub failed: Vec<(PathBuf, Box<dyn std::error::Error>)>,
+}
+
+/// Render every image in `input_dir` with the same `settings`, whose source is ignored, saving
+/// each to `output_dir` as `$SOURCE_sine` in the format of its source. Files are processed in
+/// name order, and failing images do not stop the batch, see the returned [`BatchReport`]. Only
+/// errors listing `input_dir` or creating `output_dir` are returned.
+pub fn process_dir<P: AsRef<Path>, Q: AsRef<Path>>(
+    input_dir: P,
+    output_dir: Q,
+    settings: &PlotterBuilder,
+) -> io::Result<BatchReport> {
+    let output_dir = output_dir.as_ref();
+    std::fs::create_dir_all(output_dir)?;
+    let mut sources = std::fs::read_dir(input_dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+    sources.retain(|path| path.is_file());
+    sources.sort();
+
+    let mut report = BatchReport::default();
+    for source in sources {
+        if ImageFormat::from_path(&source).is_err() {
+            report.skipped.push(source);
+            continue;
+        }
+        let mut name = source.file_stem().unwrap_or_default().to_os_string();
+        name.push("_sine.");
+        name.push(source.extension().unwrap_or_default());
+        let output = output_dir.join(name);
+        let result = settings
+            .clone()
+            .source(&source)
+            .build()
+            .map_err(|e| e.into())
+            .and_then(|mut plotter| {
+                plotter.render();
+                plotter.canvas.save(&output).map_err(|e| e.into())
+            });
+        match result {
+            Ok(()) => report.rendered.push((source, output)),
+            Err(e) => report.failed.push((source, e)),
+        }
+    }
+    Ok(report)
+}
+
+/// Total distance travelled with the pen up when drawing `strokes` in order, each from its first
+/// point to its last, in pixels.
+pub fn pen_up_travel(strokes: &[Vec<Point>]) -> f64 {
+    strokes
+        .iter()
+        .filter(|stroke| !stroke.is_empty())
+        .collect::<Vec<_>>()
+        .windows(2)
+        .map(|pair| distance(&pair[0][pair[0].len() - 1], &pair[1][0]))
+        .sum()
+}
+
+/// Reorder strokes to cut down the pen-up travel between them, see [`pen_up_travel`]. Greedy
+/// nearest neighbour: starting with the first stroke, the next one is always whichever remaining
+/// stroke has an end closest to where the pen is, reversed if that is its last point. Ties go
+/// to the earliest stroke. Empty strokes are dropped, and the strokes themselves are unchanged.
+pub fn optimize_travel(strokes: Vec<Vec<Point>>) -> Vec<Vec<Point>> {
+    let mut remaining: Vec<_> = strokes.into_iter().filter(|s| !s.is_empty()).collect();
+    if remaining.is_empty() {
+        return remaining;
+    }
+    let mut ordered = vec![remaining.remove(0)];
+    while !remaining.is_empty() {
+        let pen = *ordered[ordered.len() - 1]
+            .last()
+            .expect("strokes are not empty");
+        let (mut best, mut reverse, mut nearest) = (0, false, f64::INFINITY);
+        for (i, stroke) in remaining.iter().enumerate() {
+            let (to_start, to_end) = (
+                distance(&pen, &stroke[0]),
+                distance(&pen, &stroke[stroke.len() - 1]),
+            );
+            if to_start < nearest {
+                (best, reverse, nearest) = (i, false, to_start);
+            }
+            if to_end < nearest {
+                (best, reverse, nearest) = (i, true, to_end);
+            }
+        }
+        let mut stroke = remaining.remove(best);
+        if reverse {
+            stroke.reverse();
+        }
+        ordered.push(stroke);
+    }
+    ordered
+}
+
+/// Euclidean distance between two points, in pixels.
+fn distance(a: &Point, b: &Point) -> f64 {
+    ((a.x - b.x) as f64).hypot((a.y - b.y) as f64)
+}
+
+/// Smooth 2D value noise between -1 and 1: random values at integer coordinates, picked by
+/// `seed`, interpolated with a smoothstep in between.
+fn value_noise(seed: u64, x: f64, y: f64) -> f64 {
+    // splitmix64 of the seed and lattice point
+    let lattice = |i: i64, j: i64| {
+        let mut z = seed
+            ^ (i as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+            ^ (j as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        2.0 * (z >> 11) as f64 / (1u64 << 53) as f64 - 1.0
+    };
+    let smooth = |t: f64| t * t * (3.0 - 2.0 * t);
+    let (i, j) = (x.floor() as i64, y.floor() as i64);
+    let (tx, ty) = (smooth(x - x.floor()), smooth(y - y.floor()));
+    let top = lattice(i, j) + (lattice(i + 1, j) - lattice(i, j)) * tx;
+    let bottom = lattice(i, j + 1) + (lattice(i + 1, j + 1) - lattice(i, j + 1)) * tx;
+    top + (bottom - top) * ty
+}
+
+/// SSIM of a window of (source, rendered) gray levels, with the usual stabilizing constants.
+fn structural_similarity(pairs: &[(f64, f64)]) -> f64 {
+    let (c1, c2) = ((0.01f64 * 255.0).powi(2), (0.03f64 * 255.0).powi(2));
+    let n = pairs.len() as f64;
+    let (mean_a, mean_b) = (
+        pairs.iter().map(|p| p.0).sum::<f64>() / n,
+        pairs.iter().map(|p| p.1).sum::<f64>() / n,
+    );
+    let (mut var_a, mut var_b, mut cov) = (0.0, 0.0, 0.0);
+    for (a, b) in pairs {
+        var_a += (a - mean_a) * (a - mean_a) / n;
+        var_b += (b - mean_b) * (b - mean_b) / n;
+        cov += (a - mean_a) * (b - mean_b) / n;
+    }
+    (2.0 * mean_a * mean_b + c1) * (2.0 * cov + c2)
+        / ((mean_a * mean_a + mean_b * mean_b + c1) * (var_a + var_b + c2))
+}
+
+/// Blank canvas with the given inner size, surrounded by a border `padding_percent` percent of
+/// its smallest side wide in total, half on either side.
+fn padded_canvas(ih: u32, iw: u32, padding_percent: u32) -> Canvas {
+    let border = min(ih * padding_percent / 100, iw * padding_percent / 100);
+    Canvas::new([ih + border, iw + border], [ih, iw])
+}
+
+/// Everything that can go wrong while setting up a [`Plotter`].
+#[derive(Debug)]
+pub enum PlotterError {
+    /// Source image could not be opened.
+    Io(io::Error),
+    /// Source image could not be decoded.
+    Decode(ImageError),
+    /// Number of cells or scaling would result in an empty canvas.
+    InvalidDimensions(String),
+    /// No source image was given to the builder.
+    MissingSource,
+}
+
+impl fmt::Display for PlotterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlotterError::Io(e) => write!(f, "could not open source image: {}", e),
+            PlotterError::Decode(e) => write!(f, "could not decode source image: {}", e),
+            PlotterError::InvalidDimensions(msg) => write!(f, "invalid dimensions: {}", msg),
+            PlotterError::MissingSource => write!(f, "no source image given"),
+        }
+    }
+}
+
+impl std::error::Error for PlotterError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PlotterError::Io(e) => Some(e),
+            PlotterError::Decode(e) => Some(e),
+            PlotterError::InvalidDimensions(_) | PlotterError::MissingSource => None,
+        }
+    }
+}
+
+impl From<io::Error> for PlotterError {
+    fn from(e: io::Error) -> Self {
+        PlotterError::Io(e)
+    }
+}
+
+impl From<ImageError> for PlotterError {
+    fn from(e: ImageError) -> Self {
+        PlotterError::Decode(e)
+    }
+}
+
+/// Transfer function from the (thresholded, gamma corrected) brightness of a cell to the
+/// amplitude of its wave. Results above `max` are clamped by the [`Plotter`], which stays `Send`
+/// and `Sync` whatever its map.
+pub trait AmplitudeMap: Send + Sync {
+    fn amplitude(&self, pixel: u8, max: u32) -> u32;
+}
+
+impl<F: Fn(u8, u32) -> u32 + Send + Sync> AmplitudeMap for F {
+    fn amplitude(&self, pixel: u8, max: u32) -> u32 {
+        self(pixel, max)
+    }
+}
+
+/// Amount of work done by [`Plotter::draw_with_stats`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RenderStats {
+    /// Number of waves drawn, one per cell.
+    pub waves: usize,
+    /// Number of pixel writes, where strokes overlap pixels are counted once per write.
+    pub pixels_drawn: u64,
+    /// Smallest amplitude of any wave.
+    pub min_amplitude: u32,
+    /// Largest amplitude of any wave.
+    pub max_amplitude: u32,
+}
+
+/// How faithfully a render reproduces the tones of its source, see [`Plotter::fidelity`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fidelity {
+    /// Peak signal to noise ratio, in decibels, infinite for identical tones.
+    pub psnr: f64,
+    /// Mean structural similarity, from -1 to 1 for identical tones.
+    pub ssim: f64,
+}
+
+/// Shape of the waves drawn in every cell by [`Plotter::draw`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum StrokeShape {
+    #[default]
+    Sine,
+    Triangle,
+    Square,
+    Sawtooth,
+}
+
+impl StrokeShape {
+    /// Single wave of this shape.
+    fn wave(self, start: Point, amplitude: u32, quarter_wavelength: u32) -> ShapedWave {
+        let (a, qw) = (amplitude, quarter_wavelength);
+        match self {
+            StrokeShape::Sine => ShapedWave::Sine(Sine::new(start, a, qw)),
+            StrokeShape::Triangle => ShapedWave::Triangle(Triangle::new(start, a, qw)),
+            StrokeShape::Square => ShapedWave::Square(Square::new(start, a, qw)),
+            StrokeShape::Sawtooth => ShapedWave::Sawtooth(Sawtooth::new(start, a, qw)),
+        }
+    }
+}
+
+/// Wave of any [`StrokeShape`], see [`StrokeShape::wave`].
+enum ShapedWave {
+    Sine(Sine),
+    Triangle(Triangle),
+    Square(Square),
+    Sawtooth(Sawtooth),
+}
+
+impl Drawable for ShapedWave {
+    fn draw(&self, canvas: &mut impl XYDrawable) {
+        match self {
+            ShapedWave::Sine(wave) => wave.draw(canvas),
+            ShapedWave::Triangle(wave) => wave.draw(canvas),
+            ShapedWave::Square(wave) => wave.draw(canvas),
+            ShapedWave::Sawtooth(wave) => wave.draw(canvas),
+        }
+    }
+
+    fn draw_thick(&self, canvas: &mut impl XYDrawable, thickness: u32) {
+        match self {
+            ShapedWave::Sine(wave) => wave.draw_thick(canvas, thickness),
+            ShapedWave::Triangle(wave) => wave.draw_thick(canvas, thickness),
+            ShapedWave::Square(wave) => wave.draw_thick(canvas, thickness),
+            ShapedWave::Sawtooth(wave) => wave.draw_thick(canvas, thickness),
+        }
+    }
+
+    fn draw_antialiased<D: XYDrawable>(&self, canvas: &mut D)
+    where
+        D::Value: Gray,
+    {
+        match self {
+            ShapedWave::Sine(wave) => wave.draw_antialiased(canvas),
+            ShapedWave::Triangle(wave) => wave.draw_antialiased(canvas),
+            ShapedWave::Square(wave) => wave.draw_antialiased(canvas),
+            ShapedWave::Sawtooth(wave) => wave.draw_antialiased(canvas),
+        }
+    }
+
+    fn draw_thick_antialiased<D: XYDrawable>(&self, canvas: &mut D, thickness: u32)
+    where
+        D::Value: Gray,
+    {
+        match self {
+            ShapedWave::Sine(wave) => wave.draw_thick_antialiased(canvas, thickness),
+            ShapedWave::Triangle(wave) => wave.draw_thick_antialiased(canvas, thickness),
+            ShapedWave::Square(wave) => wave.draw_thick_antialiased(canvas, thickness),
+            ShapedWave::Sawtooth(wave) => wave.draw_thick_antialiased(canvas, thickness),
+        }
+    }
+}
+
+/// Horizontal band of the image of a canvas, addressed in the coordinates of the full canvas:
+/// image row `top` of the canvas is row 0 of `image`, and writes outside the band are dropped.
+struct Band<'a> {
+    image: &'a mut GrayImage,
+    top: u32,
+    /// Image row of the inner origin, y = 0.
+    origin: u32,
+    ow: u32,
+    ink: u8,
+}
+
+impl<'a> Band<'a> {
+    fn new(canvas: &Canvas, image: &'a mut GrayImage, top: u32) -> Self {
+        Self {
+            image,
+            top,
+            origin: canvas.fh - 1 - canvas.oh,
+            ow: canvas.ow,
+            ink: canvas.ink,
+        }
+    }
+
+    /// Band covering the whole of `image`, the image of `canvas` upscaled by `factor`, see
+    /// [`Canvas::upscaled`].
+    fn upscaled(canvas: &Canvas, image: &'a mut GrayImage, factor: u32) -> Self {
+        Self {
+            image,
+            top: 0,
+            origin: (canvas.fh - canvas.oh) * factor - 1,
+            ow: canvas.ow * factor,
+            ink: canvas.ink,
+        }
+    }
+
+    fn coords(&self, x: i32, y: i32) -> Option<(u32, u32)> {
+        let j = u32::try_from(x).ok()?.checked_add(self.ow)?;
+        let i = self.origin.checked_sub(u32::try_from(y).ok()?)?;
+        let i = i.checked_sub(self.top)?;
+        (j < self.image.width() && i < self.image.height()).then_some((j, i))
+    }
+}
+
+impl<'a> XYDrawable for Band<'a> {
+    type Value = u8;
+
+    fn ink(&self) -> u8 {
+        self.ink
+    }
+
+    fn try_get_xy(&self, x: i32, y: i32) -> Option<u8> {
+        let (j, i) = self.coords(x, y)?;
+        Some(self.image.get_pixel(j, i).0[0])
+    }
+
+    fn try_set_xy(&mut self, x: i32, y: i32, value: u8) -> bool {
+        match self.coords(x, y) {
+            Some((j, i)) => {
+                self.image.put_pixel(j, i, image::Luma([value]));
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn save_to_writer<W: io::Write>(&self, writer: &mut W, format: ImageFormat) -> ImageResult<()> {
+        write_image(self.image, writer, format)
+    }
+}
+
+/// Drawable counting the pixels written to it.
+struct Counted<'a, D: XYDrawable> {
+    canvas: &'a mut D,
+    writes: u64,
+}
+
+impl<'a, D: XYDrawable> XYDrawable for Counted<'a, D> {
+    type Value = D::Value;
+
+    fn ink(&self) -> D::Value {
+        self.canvas.ink()
+    }
+
+    fn try_get_xy(&self, x: i32, y: i32) -> Option<D::Value> {
+        self.canvas.try_get_xy(x, y)
+    }
+
+    fn try_set_xy(&mut self, x: i32, y: i32, value: D::Value) -> bool {
+        self.writes += 1;
+        self.canvas.try_set_xy(x, y, value)
+    }
+
+    fn save_to_writer<W: io::Write>(&self, writer: &mut W, format: ImageFormat) -> ImageResult<()> {
+        self.canvas.save_to_writer(writer, format)
+    }
+}
+
+/// Drawable moving every horizontal line up or down by a random amount of up to `jitter`
+/// pixels, without leaving the cell `half_height` pixels either side of `center_y`.
+struct Jittered<'a, D: XYDrawable> {
+    canvas: &'a mut D,
+    rng: &'a mut StdRng,
+    jitter: f64,
+    center_y: i32,
+    half_height: i32,
+}
+
+impl<'a, D: XYDrawable> XYDrawable for Jittered<'a, D> {
+    type Value = D::Value;
+
+    fn ink(&self) -> D::Value {
+        self.canvas.ink()
+    }
+
+    fn try_get_xy(&self, x: i32, y: i32) -> Option<D::Value> {
+        self.canvas.try_get_xy(x, y)
+    }
+
+    fn try_set_xy(&mut self, x: i32, y: i32, value: D::Value) -> bool {
+        self.canvas.try_set_xy(x, y, value)
+    }
+
+    fn set_horizontal_line(&mut self, point: &Point, value: D::Value, extent: u32) {
+        let mut point = *point;
+        if self.jitter > 0.0 {
+            let offset = self.rng.gen_range(-self.jitter..=self.jitter).round() as i32;
+            point.y = (point.y + offset).clamp(
+                self.center_y - self.half_height,
+                self.center_y + self.half_height,
+            );
+        }
+        self.canvas.set_horizontal_line(&point, value, extent);
+    }
+
+    fn save_to_writer<W: io::Write>(&self, writer: &mut W, format: ImageFormat) -> ImageResult<()> {
+        self.canvas.save_to_writer(writer, format)
+    }
+}
+
+/// Where and how the wave of a cell is drawn, see [`Plotter::row_layout`]. Every drawing mode
+/// lays out its cells this way, so that they all follow the same settings.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct CellLayout {
+    cell_x: u32,
+    cell_y: u32,
+    /// Start of the cell, on the center line of its row.
+    start: Point,
+    /// Length of the cell along its row, its wave ending that many pixels after the start.
+    width: u32,
+    /// Size of the cell across its row, which jittered strokes do not leave.
+    height: u32,
+    amplitude: u32,
+    /// Quarter wavelength of the oscillations, see [`Plotter::set_subwaves`].
+    quarter_wavelength: u32,
+    /// Number of whole oscillations from the start, the rest of the cell being a flat line.
+    oscillations: u32,
+    /// Phase of sine waves in quarter wavelengths, see [`Plotter::set_stagger`].
+    phase: u32,
+    /// Largest random wobble of the strokes, see [`Plotter::set_jitter`].
+    jitter: f64,
+}
+
+impl CellLayout {
+    /// Start of every oscillation.
+    fn starts(&self) -> impl Iterator<Item = Point> {
+        let (start, wavelength) = (self.start, 4 * self.quarter_wavelength as i32);
+        (0..self.oscillations as i32).map(move |i| start + Point::new(i * wavelength, 0))
+    }
+
+    /// Every oscillation in the given shape, only sine waves following the phase.
+    fn waves(&self, shape: StrokeShape) -> impl Iterator<Item = ShapedWave> {
+        let (a, qw, phase) = (self.amplitude, self.quarter_wavelength, self.phase);
+        self.starts().map(move |start| match shape {
+            StrokeShape::Sine => ShapedWave::Sine(Sine::with_phase(start, a, qw, phase)),
+            _ => shape.wave(start, a, qw),
+        })
+    }
+
+    /// Pixels of the flat line from the end of the oscillations to the end of the cell, if any.
+    fn tail(&self) -> impl Iterator<Item = Point> {
+        let (x, y) = (self.start.x, self.start.y);
+        let end = x + (4 * self.quarter_wavelength * self.oscillations) as i32;
+        (end + 1..=x + self.width as i32).map(move |x| Point::new(x, y))
+    }
+
+    /// Pixels of the cell drawn with sine waves, oscillation after oscillation then the tail.
+    fn sine_points(&self) -> Vec<Point> {
+        let (a, qw, n) = (self.amplitude, self.quarter_wavelength, self.oscillations);
+        let mut points = SineWave::with_phase(self.start, a, qw, n as usize, self.phase).points();
+        points.extend(self.tail());
+        points
+    }
+
+    /// Flat wave spanning the whole cell, drawn in place of any shape when the amplitude is 0.
+    fn flat_line(&self) -> HorizontalLine {
+        let half = self.width / 2;
+        HorizontalLine::new(self.start + Point::new(half as i32, 0), half)
+    }
+
+    /// Same cell on a canvas `factor` times larger, see [`Canvas::upscaled`], every pixel
+    /// becoming the center of a block of `factor` by `factor` pixels.
+    fn upscaled(self, factor: u32) -> Self {
+        let (f, center) = (factor as i32, (factor / 2) as i32);
+        Self {
+            start: Point::new(self.start.x * f + center, self.start.y * f + center),
+            width: self.width * factor,
+            height: self.height * factor,
+            amplitude: self.amplitude * factor,
+            quarter_wavelength: self.quarter_wavelength * factor,
+            jitter: self.jitter * factor as f64,
+            ..self
+        }
+    }
+}
+
+/// Where row `row` of `cells` joins the `following` one, see [`Plotter::set_connect_rows`]:
+/// rows are travelled alternately forwards and backwards, so the join is at the end of even rows
+/// and at the start of odd ones, from one center line to the other. Also gives the cell at that
+/// edge, whose stroke the join takes.
+fn row_connector<'a>(
+    row: u32,
+    cells: &'a [CellLayout],
+    following: &[CellLayout],
+) -> Option<(&'a CellLayout, Point, Point)> {
+    let (first, last, next) = (cells.first()?, cells.last()?, following.first()?);
+    let (cell, x) = if row % 2 == 1 {
+        (first, first.start.x)
+    } else {
+        (last, last.start.x + last.width as i32)
+    };
+    Some((
+        cell,
+        Point::new(x, cell.start.y),
+        Point::new(x, next.start.y),
+    ))
+}
+
+/// Pixels of the sine waves drawn by [`Plotter::draw_cell`], relative to the start of their cell,
+/// by amplitude, quarter wavelength, oscillations, phase and width.
+type Templates = BTreeMap<(u32, u32, u32, u32, u32), Vec<Point>>;
+
+/// Color drawable in "multiply" blend mode: every write multiplies the current color by the new
+/// one, channel by channel, the way overlapping inks combine in print.
+struct Multiplied<'a>(&'a mut RgbCanvas);
+
+impl<'a> XYDrawable for Multiplied<'a> {
+    type Value = Rgb<u8>;
+
+    fn ink(&self) -> Rgb<u8> {
+        self.0.ink()
+    }
+
+    fn try_get_xy(&self, x: i32, y: i32) -> Option<Rgb<u8>> {
+        self.0.try_get_xy(x, y)
+    }
+
+    fn try_set_xy(&mut self, x: i32, y: i32, value: Rgb<u8>) -> bool {
+        match self.0.try_get_xy(x, y) {
+            Some(Rgb(current)) => {
+                let product =
+                    [0, 1, 2].map(|c| (current[c] as u32 * value.0[c] as u32 / 255) as u8);
+                self.0.try_set_xy(x, y, Rgb(product))
+            }
+            None => false,
+        }
+    }
+
+    fn save_to_writer<W: io::Write>(&self, writer: &mut W, format: ImageFormat) -> ImageResult<()> {
+        self.0.save_to_writer(writer, format)
+    }
+}
+
+/// Sizes computed by [`Plotter::plan`], all in pixels unless told otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RenderPlan {
+    /// Full width of the canvas.
+    pub fw: u32,
+    /// Full height of the canvas.
+    pub fh: u32,
+    /// Inner width of the canvas, where the waves are drawn.
+    pub iw: u32,
+    /// Inner height of the canvas, where the waves are drawn.
+    pub ih: u32,
+    pub cell_width: u32,
+    pub cell_height: u32,
+    pub quarter_wavelength: u32,
+    /// Largest amplitude of any wave, before leaving room for the stroke thickness.
+    pub max_amplitude: u32,
+    /// Number of cells, i.e. of waves to draw.
+    pub cells: usize,
+}
+
+/// Default mapping, proportional to darkness: black gets the full amplitude and white none.
+/// Inverted, this is proportional to brightness instead.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LinearMap {
+    pub invert: bool,
+}
+
+impl AmplitudeMap for LinearMap {
+    fn amplitude(&self, pixel: u8, max: u32) -> u32 {
+        let pixel = pixel as u32;
+        if self.invert {
+            max * pixel / 255
+        } else {
+            max - max * pixel / 255
+        }
+    }
+}
+
+/// Transfer function applied to the brightness of every cell before it is mapped to an
+/// amplitude, see [`Plotter::set_mapping`].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Mapping {
+    /// Brightness as is.
+    #[default]
+    Linear,
+    /// Brightness raised to the given power, above 1 darkening midtones and below 1 lightening
+    /// them, as with [`Plotter::set_gamma`].
+    Gamma(f64),
+    /// Logarithmic response, lifting shadows the most, for smaller waves in dark areas.
+    Log,
+    /// sRGB decoding to linear light, darkening midtones.
+    Srgb,
+}
+
+impl Mapping {
+    /// Brightness from 0 to 1 of a pixel value.
+    pub fn factor(self, pixel: u8) -> f64 {
+        let value = pixel as f64 / 255.0;
+        match self {
+            Mapping::Linear => value,
+            Mapping::Gamma(gamma) => value.powf(gamma.max(0.0)),
+            Mapping::Log => (1.0 + pixel as f64).ln() / 256f64.ln(),
+            Mapping::Srgb if value <= 0.04045 => value / 12.92,
+            Mapping::Srgb => ((value + 0.055) / 1.055).powf(2.4),
+        }
+    }
+}
 
 /// Core crate component, takes a source image, resizes it to a number of cells, and plots those
 /// cells to the canvas using sine waves.
 pub struct Plotter {
     source: GrayImage,
+    /// Color of every cell, for [`Plotter::draw_colored`], or `None` if the cells are gray.
+    colors: Option<RgbImage>,
+    /// Full resolution source, kept for [`Plotter::draw_with_underlay`], or `None` if it is the
+    /// grid itself.
+    original: Option<GrayImage>,
     pub canvas: Canvas,
     threshold: u8,
+    amplitude_map: Arc<dyn AmplitudeMap>,
+    gamma: f64,
+    thickness: u32,
+    /// Fraction of the cell height spanned by the largest waves, crest to trough.
+    amplitude_ratio: f64,
+    /// Smallest amplitude, as a fraction of the largest one.
+    amplitude_floor: f64,
+    /// Physical size of a canvas pixel, for G-code export.
+    mm_per_pixel: f64,
+    /// Number of brightness levels cells are posterized to, if any.
+    quantize_levels: Option<u32>,
+    mapping: Mapping,
+    shape: StrokeShape,
+    /// Number of whole oscillations drawn in every cell.
+    subwaves: u32,
+    /// Whether odd rows start half a period late.
+    stagger: bool,
+    /// Whether row ends are joined into a single line.
+    connect_rows: bool,
+    /// Wavelength of the oscillations in pixels, whatever the cell size, if fixed.
+    wavelength_px: Option<u32>,
+    /// Largest random vertical offset of stroke points, in pixels.
+    jitter: f64,
+    /// Seed of the random jitter and of the amplitude noise.
+    seed: u64,
+    /// Largest change of amplitude by the noise field, as a fraction of the maximum.
+    noise_strength: f64,
+    /// Size of the features of the noise field, in cells.
+    noise_scale: f64,
 }
 
-impl Plotter {
-    pub fn new<P: AsRef<Path>>(nw: u32, nh: u32, source: P, scale: u32, threshold: u8) -> Self {
-        let source = ImageReader::open(source)
-            .expect("could not open source image")
-            .decode()
-            .expect("could not decode source image");
+/// Every knob of a [`Plotter`], to save and load them all at once, e.g. as TOML or JSON with
+/// the `serde` feature. Missing fields take their default, the same as the CLI's. See
+/// [`Plotter::from_settings`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct PlotterSettings {
+    /// Number of cells horizontally, oscillations per row.
+    pub nw: u32,
+    /// Number of cells vertically, rows.
+    pub nh: u32,
+    /// Percentage scaling of the source image resolution.
+    pub scale: u32,
+    /// Threshold for white values, so sine waves do not end up completely flat.
+    pub threshold: u8,
+    /// Thickness of the strokes used by [`Plotter::render`].
+    pub thickness: u32,
+    /// See [`Plotter::set_invert`].
+    pub invert: bool,
+    /// See [`Plotter::set_gamma`].
+    pub gamma: f64,
+    /// See [`Plotter::set_amplitude_ratio`].
+    pub amplitude_ratio: f64,
+    /// See [`Plotter::set_amplitude_floor`].
+    pub amplitude_floor: f64,
+    /// See [`Plotter::set_padding_percent`].
+    pub padding_percent: u32,
+    /// Filter used to resize the source image down to one pixel per cell.
+    #[cfg_attr(feature = "serde", serde(with = "filter_name"))]
+    pub filter: FilterType,
+    /// See [`Plotter::set_quantize_levels`].
+    pub quantize_levels: Option<u32>,
+    /// See [`Plotter::set_mapping`].
+    pub mapping: Mapping,
+    /// See [`Plotter::set_shape`].
+    pub shape: StrokeShape,
+    /// See [`Plotter::set_subwaves`].
+    pub subwaves: u32,
+    /// See [`Plotter::set_stagger`].
+    pub stagger: bool,
+    /// See [`Plotter::set_connect_rows`].
+    pub connect_rows: bool,
+    /// See [`Plotter::set_wavelength_px`].
+    pub wavelength_px: Option<u32>,
+    /// See [`Plotter::set_jitter`].
+    pub jitter: f64,
+    /// Seed of the jitter and noise, see [`Plotter::set_jitter`].
+    pub seed: u64,
+    /// See [`Plotter::set_noise`].
+    pub noise_strength: f64,
+    /// See [`Plotter::set_noise`].
+    pub noise_scale: f64,
+}
 
-        let nw_scale = nw * 4;
+impl Default for PlotterSettings {
+    fn default() -> Self {
+        Self {
+            nw: 50,
+            nh: 50,
+            scale: 100,
+            threshold: 200,
+            thickness: 4,
+            invert: false,
+            gamma: 1.0,
+            amplitude_ratio: 0.9,
+            amplitude_floor: 0.0,
+            padding_percent: 5,
+            filter: FilterType::Triangle,
+            quantize_levels: None,
+            mapping: Mapping::Linear,
+            shape: StrokeShape::Sine,
+            subwaves: 1,
+            stagger: false,
+            connect_rows: false,
+            wavelength_px: None,
+            jitter: 0.0,
+            seed: 0,
+            noise_strength: 0.0,
+            noise_scale: 8.0,
+        }
+    }
+}
 
-        let target_width = (source.width() * scale / 100 / nw_scale + 1) * nw_scale + 1;
-        let target_height = (source.height() * target_width) / source.width();
-        let border = min(target_height * 5 / 100, target_width * 5 / 100);
+/// Serialization of [`FilterType`] by name, e.g. `"lanczos3"`.
+#[cfg(feature = "serde")]
+mod filter_name {
+    use image::imageops::FilterType;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
 
-        let canvas = Canvas::new(
-            [target_height + border, target_width + border],
-            [target_height, target_width],
-        );
+    const FILTERS: [FilterType; 5] = [
+        FilterType::Nearest,
+        FilterType::Triangle,
+        FilterType::CatmullRom,
+        FilterType::Gaussian,
+        FilterType::Lanczos3,
+    ];
+    const NAMES: [&str; 5] = ["nearest", "triangle", "catmull_rom", "gaussian", "lanczos3"];
 
-        Self {
-            source: source
-                .resize_exact(nw, nh, FilterType::Triangle)
-                .into_luma8(),
-            canvas,
-            threshold,
-        }
+    pub fn serialize<S: Serializer>(filter: &FilterType, serializer: S) -> Result<S::Ok, S::Error> {
+        let i = FILTERS
+            .iter()
+            .position(|f| f == filter)
+            .expect("every filter has a name");
+        serializer.serialize_str(NAMES[i])
     }
 
-    fn cell_height(&self) -> u32 {
-        self.canvas.ih / self.source.height()
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<FilterType, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        NAMES
+            .iter()
+            .position(|n| *n == name)
+            .map(|i| FILTERS[i])
+            .ok_or_else(|| D::Error::unknown_variant(&name, &NAMES))
     }
+}
 
-    fn cell_width(&self) -> u32 {
-        (self.canvas.iw - 1) / self.source.width()
+/// Step by step configuration of a [`Plotter`], starting from the same defaults as the CLI.
+#[derive(Debug, Clone, Default)]
+pub struct PlotterBuilder {
+    source: Option<PathBuf>,
+    settings: PlotterSettings,
+}
+
+impl PlotterBuilder {
+    pub fn new() -> Self {
+        Self::default()
     }
 
-    /// Return the max amplitude a sine wave can have. A_max = 0.9 x cell_height / 2.
-    fn max_amplitude(&self) -> u32 {
-        self.cell_height() * 9 / 20
+    /// Path of the source image, the only setting without a default.
+    pub fn source<P: AsRef<Path>>(mut self, source: P) -> Self {
+        self.source = Some(source.as_ref().to_path_buf());
+        self
     }
 
-    fn quarter_wavelength(&self) -> u32 {
-        self.cell_width() / 4
+    /// Every setting at once, replacing those set so far. The source is kept.
+    pub fn settings(mut self, settings: PlotterSettings) -> Self {
+        self.settings = settings;
+        self
     }
 
-    fn cell_to_sine_start_y(&self, cell_y: u32) -> u32 {
-        (self.canvas.ih / 2 + self.canvas.ih * (self.source.height() - cell_y - 1))
-            / self.source.height()
+    /// Number of cells horizontally (oscillations per row) and vertically (rows).
+    pub fn cells(mut self, nw: u32, nh: u32) -> Self {
+        self.settings.nw = nw;
+        self.settings.nh = nh;
+        self
     }
 
-    fn get_pixel_as_u32(&self, x: u32, y: u32) -> u32 {
-        min(self.source.get_pixel(x, y).0[0], self.threshold) as u32
+    /// Percentage scaling of the source image resolution.
+    pub fn scale(mut self, scale: u32) -> Self {
+        self.settings.scale = scale;
+        self
     }
 
-    pub fn draw(&mut self, thickness: u32) {
-        let cw = self.cell_width();
-        let qwave = self.quarter_wavelength();
-        let amax = self.max_amplitude();
-        let mut x: u32;
-        let mut y: u32;
-        let mut a: u32;
-        let mut sine: Sine;
+    /// Threshold for white values, so sine waves do not end up completely flat.
+    pub fn threshold(mut self, threshold: u8) -> Self {
+        self.settings.threshold = threshold;
+        self
+    }
 
-        for cell_y in 0..self.source.height() {
-            for cell_x in 0..self.source.width() {
-                x = cw * cell_x;
+    /// Thickness of the strokes used by [`Plotter::render`].
+    pub fn thickness(mut self, thickness: u32) -> Self {
+        self.settings.thickness = thickness;
+        self
+    }
 
-                // calculate every time to avoid period falling behind
-                y = self.cell_to_sine_start_y(cell_y);
-                a = amax - amax * self.get_pixel_as_u32(cell_x, cell_y) / 255;
-                sine = Sine::new(Point::new(x, y), a, qwave);
-                sine.draw_thick(&mut self.canvas, thickness)
-            }
-        }
+    /// See [`Plotter::set_invert`].
+    pub fn invert(mut self, invert: bool) -> Self {
+        self.settings.invert = invert;
+        self
+    }
+
+    /// See [`Plotter::set_gamma`].
+    pub fn gamma(mut self, gamma: f64) -> Self {
+        self.settings.gamma = gamma;
+        self
+    }
+
+    /// See [`Plotter::set_amplitude_ratio`].
+    pub fn amplitude_ratio(mut self, ratio: f64) -> Self {
+        self.settings.amplitude_ratio = ratio;
+        self
+    }
+
+    /// See [`Plotter::set_amplitude_floor`].
+    pub fn amplitude_floor(mut self, floor: f64) -> Self {
+        self.settings.amplitude_floor = floor;
+        self
+    }
+
+    /// See [`Plotter::set_padding_percent`].
+    pub fn padding_percent(mut self, padding_percent: u32) -> Self {
+        self.settings.padding_percent = padding_percent;
+        self
+    }
+
+    /// Filter used to resize the source image down to one pixel per cell, e.g.
+    /// [`FilterType::Nearest`] for crisp pixel art or [`FilterType::Lanczos3`] for photos.
+    pub fn filter(mut self, filter: FilterType) -> Self {
+        self.settings.filter = filter;
+        self
+    }
+
+    /// See [`Plotter::set_quantize_levels`].
+    pub fn quantize_levels(mut self, levels: Option<u32>) -> Self {
+        self.settings.quantize_levels = levels;
+        self
+    }
+
+    /// See [`Plotter::set_mapping`].
+    pub fn mapping(mut self, mapping: Mapping) -> Self {
+        self.settings.mapping = mapping;
+        self
+    }
+
+    /// See [`Plotter::set_shape`].
+    pub fn shape(mut self, shape: StrokeShape) -> Self {
+        self.settings.shape = shape;
+        self
+    }
+
+    /// See [`Plotter::set_subwaves`].
+    pub fn subwaves(mut self, subwaves: u32) -> Self {
+        self.settings.subwaves = subwaves;
+        self
+    }
+
+    /// See [`Plotter::set_stagger`].
+    pub fn stagger(mut self, stagger: bool) -> Self {
+        self.settings.stagger = stagger;
+        self
+    }
+
+    /// See [`Plotter::set_connect_rows`].
+    pub fn connect_rows(mut self, connect_rows: bool) -> Self {
+        self.settings.connect_rows = connect_rows;
+        self
+    }
+
+    /// See [`Plotter::set_wavelength_px`].
+    pub fn wavelength_px(mut self, wavelength: Option<u32>) -> Self {
+        self.settings.wavelength_px = wavelength;
+        self
+    }
+
+    /// See [`Plotter::set_jitter`].
+    pub fn jitter(mut self, jitter: f64, seed: u64) -> Self {
+        self.settings.jitter = jitter;
+        self.settings.seed = seed;
+        self
+    }
+
+    /// See [`Plotter::set_noise`].
+    pub fn noise(mut self, strength: f64, scale: f64) -> Self {
+        self.settings.noise_strength = strength;
+        self.settings.noise_scale = scale;
+        self
+    }
+
+    pub fn build(self) -> Result<Plotter, PlotterError> {
+        let source = self.source.ok_or(PlotterError::MissingSource)?;
+        let source = ImageReader::open(source)?.decode()?;
+        Plotter::from_settings(source, &self.settings)
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::canvas::XYDrawable;
+impl Plotter {
+    pub fn new<P: AsRef<Path>>(
+        nw: u32,
+        nh: u32,
+        source: P,
+        scale: u32,
+        threshold: u8,
+    ) -> Result<Self, PlotterError> {
+        let source = ImageReader::open(source)?.decode()?;
+        Self::from_image(nw, nh, source, scale, threshold)
+    }
 
-    #[test]
-    #[ignore = "visual check"]
-    fn logo() {
-        let mut plotter = Plotter::new(50, 50, "tests/lincoln.jpeg", 100, 255);
-        plotter.draw(4);
-        plotter.canvas.save("tests/lincoln_sine.jpg");
+    /// Plotter for an already decoded image, configured with every knob of `settings`.
+    pub fn from_settings(
+        source: DynamicImage,
+        settings: &PlotterSettings,
+    ) -> Result<Self, PlotterError> {
+        let mut plotter = Plotter::from_image_filtered(
+            settings.nw,
+            settings.nh,
+            source,
+            settings.scale,
+            settings.threshold,
+            settings.filter,
+        )?;
+        plotter.set_invert(settings.invert);
+        plotter.set_gamma(settings.gamma);
+        plotter.set_amplitude_ratio(settings.amplitude_ratio);
+        plotter.set_amplitude_floor(settings.amplitude_floor);
+        plotter.set_padding_percent(settings.padding_percent);
+        plotter.set_quantize_levels(settings.quantize_levels);
+        plotter.set_mapping(settings.mapping);
+        plotter.set_shape(settings.shape);
+        plotter.set_subwaves(settings.subwaves);
+        plotter.set_stagger(settings.stagger);
+        plotter.set_connect_rows(settings.connect_rows);
+        plotter.set_wavelength_px(settings.wavelength_px);
+        plotter.set_jitter(settings.jitter, settings.seed);
+        plotter.set_noise(settings.noise_strength, settings.noise_scale);
+        plotter.thickness = settings.thickness;
+        Ok(plotter)
+    }
+
+    /// Same as [`Plotter::new`], for an image that has already been decoded.
+    pub fn from_image(
+        nw: u32,
+        nh: u32,
+        source: DynamicImage,
+        scale: u32,
+        threshold: u8,
+    ) -> Result<Self, PlotterError> {
+        Self::from_image_filtered(nw, nh, source, scale, threshold, FilterType::Triangle)
+    }
+
+    /// Same as [`Plotter::from_image`], resizing the source with the given filter.
+    fn from_image_filtered(
+        nw: u32,
+        nh: u32,
+        source: DynamicImage,
+        scale: u32,
+        threshold: u8,
+        filter: FilterType,
+    ) -> Result<Self, PlotterError> {
+        if nw == 0 || nh == 0 || scale == 0 {
+            return Err(PlotterError::InvalidDimensions(format!(
+                "cells {}x{} at {}% scale",
+                nw, nh, scale
+            )));
+        }
+
+        // smallest whole number of waves per cell covering the scaled source, so that it is never
+        // shrunk, computed in 64 bits as large sources times scale can overflow
+        let scaled_width = source.width() as u64 * scale as u64 / 100;
+        let nw_scale = nw as u64 * 4;
+        let quarter_wavelength = max(scaled_width.div_ceil(nw_scale), 1);
+        let target_width = u32::try_from(quarter_wavelength * nw_scale + 1).map_err(|_| {
+            PlotterError::InvalidDimensions(format!(
+                "{} pixels wide source at {}% scale",
+                source.width(),
+                scale
+            ))
+        })?;
+        Self::with_inner_width(nw, nh, source, target_width, threshold, filter)
+    }
+
+    /// Plotter drawing one cell per pixel of `grid`, taken as is rather than resized from a
+    /// source image, e.g. for a heatmap of data. Cells are square, `cell_size` pixels wide
+    /// rounded up to a multiple of four, and every gray level counts, only white giving flat
+    /// waves.
+    pub fn from_grid(grid: GrayImage, cell_size: u32) -> Result<Self, PlotterError> {
+        let (nw, nh) = grid.dimensions();
+        let cell = max(cell_size.div_ceil(4), MIN_CELL_SIZE / 4) as u64 * 4;
+        let size = |cells: u32| {
+            u32::try_from(cells as u64 * cell)
+                .ok()
+                .filter(|_| cells > 0)
+        };
+        let (iw, ih) = match (size(nw).and_then(|w| w.checked_add(1)), size(nh)) {
+            (Some(iw), Some(ih)) => (iw, ih),
+            _ => {
+                return Err(PlotterError::InvalidDimensions(format!(
+                    "grid of {}x{} cells at {} pixels per cell",
+                    nw, nh, cell_size
+                )))
+            }
+        };
+        let canvas = padded_canvas(ih, iw, 5);
+        Ok(Self::with_grid(grid, None, None, canvas, 255))
+    }
+
+    /// Same as [`Plotter::new`], sized to get as close as possible to an inner canvas
+    /// `target_width` pixels wide rather than by scaling the source. The actual width is always
+    /// a multiple of four pixels per cell, plus one, so that every cell fits a whole wave.
+    pub fn with_output_size<P: AsRef<Path>>(
+        nw: u32,
+        nh: u32,
+        source: P,
+        target_width: u32,
+        threshold: u8,
+    ) -> Result<Self, PlotterError> {
+        if nw == 0 || nh == 0 || target_width == 0 {
+            return Err(PlotterError::InvalidDimensions(format!(
+                "cells {}x{} at {} pixels wide",
+                nw, nh, target_width
+            )));
+        }
+
+        let source = ImageReader::open(source)?.decode()?;
+        // in 64 bits, as rounding to whole waves per cell can push the width past u32::MAX
+        let nw_scale = nw as u64 * 4;
+        let quarter_wavelength = ((target_width - 1) as f64 / nw_scale as f64).round();
+        let target_width = u32::try_from(max(quarter_wavelength as u64, 1) * nw_scale + 1)
+            .map_err(|_| {
+                PlotterError::InvalidDimensions(format!(
+                    "cells {}x{} at {} pixels wide",
+                    nw, nh, target_width
+                ))
+            })?;
+        Self::with_inner_width(
+            nw,
+            nh,
+            source,
+            target_width,
+            threshold,
+            FilterType::Triangle,
+        )
+    }
+
+    /// Plotter whose inner canvas is `target_width` pixels wide, its height following the
+    /// aspect ratio of the source. Callers pick widths of `4 * quarter_wavelength * nw + 1`, so
+    /// that cells tile the canvas exactly, each one [`Plotter::cell_width`] wide holding a whole
+    /// wave, with one more pixel for the last wave to end on. Fails if the source has fewer pixels
+    /// than there are cells, if the cells would be less than [`MIN_CELL_SIZE`] pixels wide or
+    /// high, or if the canvas height would not fit in a `u32`.
+    fn with_inner_width(
+        nw: u32,
+        nh: u32,
+        source: DynamicImage,
+        target_width: u32,
+        threshold: u8,
+        filter: FilterType,
+    ) -> Result<Self, PlotterError> {
+        if source.width() < nw || source.height() < nh {
+            return Err(PlotterError::InvalidDimensions(format!(
+                "source of {}x{} pixels for {}x{} cells",
+                source.width(),
+                source.height(),
+                nw,
+                nh
+            )));
+        }
+        let target_height = source.height() as u64 * target_width as u64 / source.width() as u64;
+        let target_height = u32::try_from(target_height).map_err(|_| {
+            PlotterError::InvalidDimensions(format!(
+                "{}x{} pixels source at {} pixels wide",
+                source.width(),
+                source.height(),
+                target_width
+            ))
+        })?;
+        let (cw, ch) = ((target_width - 1) / nw, target_height / nh);
+        if cw < MIN_CELL_SIZE || ch < MIN_CELL_SIZE {
+            return Err(PlotterError::InvalidDimensions(format!(
+                "cells of {}x{} pixels, at least {} needed on each side",
+                cw, ch, MIN_CELL_SIZE
+            )));
+        }
+        let canvas = padded_canvas(target_height, target_width, 5);
+        let grid = source.resize_exact(nw, nh, filter);
+        Ok(Self::with_grid(
+            grid.to_luma8(),
+            Some(grid.into_rgb8()),
+            Some(source.into_luma8()),
+            canvas,
+            threshold,
+        ))
+    }
+
+    /// Plotter drawing one cell per pixel of `grid`, onto `canvas`. `colors` are those of the
+    /// cells, if not gray, and `original` the full resolution image the grid stands for, if not
+    /// the grid itself.
+    fn with_grid(
+        grid: GrayImage,
+        colors: Option<RgbImage>,
+        original: Option<GrayImage>,
+        canvas: Canvas,
+        threshold: u8,
+    ) -> Self {
+        Self {
+            source: grid,
+            colors,
+            original,
+            canvas,
+            threshold,
+            amplitude_map: Arc::new(LinearMap::default()),
+            gamma: 1.0,
+            thickness: 4,
+            amplitude_ratio: 0.9,
+            amplitude_floor: 0.0,
+            mm_per_pixel: 0.1,
+            quantize_levels: None,
+            mapping: Mapping::Linear,
+            shape: StrokeShape::Sine,
+            subwaves: 1,
+            stagger: false,
+            connect_rows: false,
+            wavelength_px: None,
+            jitter: 0.0,
+            seed: 0,
+            noise_strength: 0.0,
+            noise_scale: 8.0,
+        }
+    }
+
+    /// Quick, low resolution version of [`Plotter::render`], at most `max_dim` pixels wide and
+    /// high border included, or as small as cells allow. Waves, strokes and every other
+    /// size in pixels are scaled down alike, and drawn the same way, rather than by downscaling a
+    /// full render.
+    pub fn preview(&self, max_dim: u32) -> GrayImage {
+        let (iw, ih) = (self.canvas.iw, self.canvas.ih);
+        let (nw, nh) = self.source.dimensions();
+        let factor = (max_dim as f64 / max(self.canvas.fw, self.canvas.fh) as f64).min(1.0);
+        let scaled = |size: u32| (size as f64 * factor) as u32;
+
+        // whole waves per cell as for a full render, and cells at least MIN_CELL_SIZE high
+        let min_qwave = max(
+            (MIN_CELL_SIZE * nh * (iw - 1)).div_ceil(4 * nw * ih),
+            MIN_CELL_SIZE / 4,
+        );
+        let qwave = max(scaled(self.quarter_wavelength()), min_qwave);
+        let preview_iw = 4 * qwave * nw + 1;
+        let preview_ih = ih * (preview_iw - 1) / (iw - 1);
+        let factor = (preview_iw - 1) as f64 / (iw - 1) as f64;
+        let scaled = |size: u32| (size as f64 * factor) as u32;
+        let border = (
+            scaled(self.canvas.fh - self.canvas.ih),
+            scaled(self.canvas.fw - self.canvas.iw),
+        );
+        let mut canvas = Canvas::new(
+            [preview_ih + border.0, preview_iw + border.1],
+            [preview_ih, preview_iw],
+        );
+        canvas.ink = self.canvas.ink;
+
+        let mut preview = Self {
+            source: self.source.clone(),
+            colors: self.colors.clone(),
+            original: self.original.clone(),
+            canvas,
+            threshold: self.threshold,
+            amplitude_map: self.amplitude_map.clone(),
+            gamma: self.gamma,
+            thickness: scaled(self.thickness),
+            amplitude_ratio: self.amplitude_ratio,
+            amplitude_floor: self.amplitude_floor,
+            mm_per_pixel: self.mm_per_pixel / factor,
+            quantize_levels: self.quantize_levels,
+            mapping: self.mapping,
+            shape: self.shape,
+            subwaves: self.subwaves,
+            stagger: self.stagger,
+            connect_rows: self.connect_rows,
+            wavelength_px: self.wavelength_px.map(|w| max(scaled(w), 1)),
+            jitter: self.jitter * factor,
+            seed: self.seed,
+            noise_strength: self.noise_strength,
+            noise_scale: self.noise_scale,
+        };
+        preview.render();
+        preview.canvas.image().clone()
+    }
+
+    /// Compare what has been drawn so far with the source: the inner canvas is averaged back down
+    /// to one pixel per cell, then compared with the brightness of the cells, giving higher
+    /// scores the closer the tones. SSIM is averaged over 8 x 8 windows of cells, or computed over
+    /// all of them if there are fewer.
+    pub fn fidelity(&self) -> Fidelity {
+        let canvas = &self.canvas;
+        let (nw, nh) = self.source.dimensions();
+        let top = canvas.fh - canvas.oh - canvas.ih;
+        let inner = imageops::crop_imm(canvas.image(), canvas.ow, top, canvas.iw, canvas.ih);
+        let rendered = imageops::resize(&inner.to_image(), nw, nh, FilterType::Triangle);
+
+        let pairs = |x0: u32, y0: u32, w: u32, h: u32| {
+            (y0..y0 + h)
+                .flat_map(move |y| (x0..x0 + w).map(move |x| (x, y)))
+                .map(|(x, y)| {
+                    let source = self.source.get_pixel(x, y).0[0] as f64;
+                    (source, rendered.get_pixel(x, y).0[0] as f64)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let all = pairs(0, 0, nw, nh);
+        let mse = all.iter().map(|(a, b)| (a - b) * (a - b)).sum::<f64>() / all.len() as f64;
+        let psnr = 10.0 * (255.0 * 255.0 / mse).log10();
+
+        let (ww, wh) = (min(nw, 8), min(nh, 8));
+        let mut ssim = 0.0;
+        for y in 0..=nh - wh {
+            for x in 0..=nw - ww {
+                ssim += structural_similarity(&pairs(x, y, ww, wh));
+            }
+        }
+        let windows = ((nw - ww + 1) * (nh - wh + 1)) as f64;
+        Fidelity {
+            psnr,
+            ssim: ssim / windows,
+        }
+    }
+
+    /// Source image resized to one pixel per cell, the brightness values waves are drawn from.
+    pub fn source_grid(&self) -> &GrayImage {
+        &self.source
+    }
+
+    /// Full resolution image the cells stand for.
+    fn original(&self) -> &GrayImage {
+        self.original.as_ref().unwrap_or(&self.source)
+    }
+
+    /// Color of the cell at `x`, `y`, its gray level if the cells have no colors.
+    fn color(&self, x: u32, y: u32) -> Rgb<u8> {
+        match &self.colors {
+            Some(colors) => *colors.get_pixel(x, y),
+            None => {
+                let value = self.source.get_pixel(x, y).0[0];
+                Rgb([value; 3])
+            }
+        }
+    }
+
+    /// Brightness of every cell, row by row from the top, before thresholding.
+    pub fn dump_grid(&self) -> Vec<Vec<u8>> {
+        self.source
+            .rows()
+            .map(|row| row.map(|pixel| pixel.0[0]).collect())
+            .collect()
+    }
+
+    /// Dimensions of the render, without drawing anything, e.g. to check the output size first.
+    pub fn plan(&self) -> RenderPlan {
+        RenderPlan {
+            fw: self.canvas.fw,
+            fh: self.canvas.fh,
+            iw: self.canvas.iw,
+            ih: self.canvas.ih,
+            cell_width: self.cell_width(),
+            cell_height: self.cell_height(),
+            quarter_wavelength: self.quarter_wavelength(),
+            max_amplitude: self.max_amplitude(),
+            cells: (self.source.width() * self.source.height()) as usize,
+        }
+    }
+
+    /// Spread the brightness of the cells over the whole 0 - 255 range by histogram equalization,
+    /// for more contrast in the waves of dull images. Uniform images are left as they are.
+    pub fn equalize(&mut self) {
+        let mut cdf = [0u32; 256];
+        for pixel in self.source.pixels() {
+            cdf[pixel.0[0] as usize] += 1;
+        }
+        for i in 1..256 {
+            cdf[i] += cdf[i - 1];
+        }
+        let total = cdf[255];
+        let lowest = *cdf.iter().find(|&&count| count > 0).unwrap_or(&0);
+        if total == lowest {
+            return;
+        }
+        // a grid standing for itself must keep its original levels
+        if self.original.is_none() {
+            self.original = Some(self.source.clone());
+        }
+        for pixel in self.source.pixels_mut() {
+            let rank = (cdf[pixel.0[0] as usize] - lowest) as f64 / (total - lowest) as f64;
+            pixel.0[0] = (255.0 * rank).round() as u8;
+        }
+    }
+
+    /// Height of every cell, in canvas pixels. Rows are spread evenly over the inner canvas, so
+    /// when its height is not a multiple of the number of rows some are a pixel taller.
+    pub fn cell_height(&self) -> u32 {
+        self.canvas.ih / self.source.height()
+    }
+
+    /// Width of every cell, in canvas pixels, always a multiple of four. Cell `x` spans the
+    /// inner canvas columns from `x * cell_width` to `(x + 1) * cell_width`, ends included.
+    pub fn cell_width(&self) -> u32 {
+        (self.canvas.iw - 1) / self.source.width()
+    }
+
+    /// Return the max amplitude a sine wave can have, in canvas pixels from the center line to
+    /// the crest. A_max = ratio x cell_height / 2.
+    pub fn max_amplitude(&self) -> u32 {
+        (self.cell_height() as f64 * self.amplitude_ratio / 2.0) as u32
+    }
+
+    /// Largest amplitude used when drawing strokes of the given thickness, leaving `thickness`
+    /// pixels of headroom above and below each wave so that thick strokes on neighbouring rows
+    /// do not run into each other.
+    pub fn effective_max_amplitude(&self, thickness: u32) -> u32 {
+        self.max_amplitude().saturating_sub(thickness)
+    }
+
+    /// Quarter wavelength of the waves, in canvas pixels: a quarter of [`Plotter::cell_width`].
+    pub fn quarter_wavelength(&self) -> u32 {
+        self.cell_width() / 4
+    }
+
+    /// Y coordinate of the center line of row `cell_y` of cells, counted from the top, in the
+    /// cartesian coordinates of the inner canvas, see [`XYDrawable::set_xy`].
+    pub fn cell_to_sine_start_y(&self, cell_y: u32) -> u32 {
+        (self.canvas.ih / 2 + self.canvas.ih * (self.source.height() - cell_y - 1))
+            / self.source.height()
+    }
+
+    fn get_pixel_as_u32(&self, x: u32, y: u32) -> u32 {
+        min(self.source.get_pixel(x, y).0[0], self.threshold) as u32
+    }
+
+    /// Flip the brightness mapping, so that bright cells get the largest amplitudes instead of
+    /// dark ones. The threshold then caps the amplitude rather than setting its minimum. This
+    /// replaces any custom amplitude map with a [`LinearMap`].
+    pub fn set_invert(&mut self, invert: bool) {
+        self.amplitude_map = Arc::new(LinearMap { invert });
+    }
+
+    /// Use a custom transfer function from brightness to amplitude, e.g. a closure
+    /// `|pixel: u8, max: u32| -> u32`.
+    pub fn with_amplitude_map(mut self, map: impl AmplitudeMap + 'static) -> Self {
+        self.amplitude_map = Arc::new(map);
+        self
+    }
+
+    /// Fraction of the cell height covered by the largest waves, 0.9 by default. Lower values
+    /// leave room for thick strokes, so neighbouring rows do not touch. Clamped to (0, 1].
+    pub fn set_amplitude_ratio(&mut self, ratio: f64) {
+        self.amplitude_ratio = ratio.clamp(f64::EPSILON, 1.0);
+    }
+
+    /// Smallest amplitude of any wave, as a fraction of the largest one, so that even white
+    /// areas show some texture instead of flat lines. 0 by default, clamped to [0, 1].
+    pub fn set_amplitude_floor(&mut self, floor: f64) {
+        self.amplitude_floor = floor.clamp(0.0, 1.0);
+    }
+
+    /// Size of the blank border around the drawing, as a percentage of its smallest side, 5 by
+    /// default. 0 leaves no border at all, e.g. for tiling. This replaces the canvas with a blank
+    /// one of the same inner size.
+    pub fn set_padding_percent(&mut self, padding_percent: u32) {
+        self.canvas = padded_canvas(self.canvas.ih, self.canvas.iw, padding_percent);
+    }
+
+    /// Physical size of a canvas pixel used by [`Plotter::to_gcode`], 0.1 mm by default.
+    pub fn set_mm_per_pixel(&mut self, mm_per_pixel: f64) {
+        self.mm_per_pixel = mm_per_pixel;
+    }
+
+    /// Gamma applied to the pixel values before mapping them to amplitudes. Values above 1 darken
+    /// midtones, producing larger waves, and values below 1 lighten them. Negative values are
+    /// treated as 0, which maps every pixel to white.
+    pub fn set_gamma(&mut self, gamma: f64) {
+        self.gamma = gamma.max(0.0);
+    }
+
+    /// Transfer function from the brightness of the cells to that used for their amplitudes,
+    /// [`Mapping::Linear`] by default. It is applied first, before gamma and quantization.
+    pub fn set_mapping(&mut self, mapping: Mapping) {
+        self.mapping = mapping;
+    }
+
+    /// Posterize the brightness of the cells to `levels` evenly spaced values, black and white
+    /// included, for a stylized look with as many distinct amplitudes. Applied after the
+    /// threshold and gamma, `None` by default. Fewer than 2 levels are treated as 2.
+    pub fn set_quantize_levels(&mut self, levels: Option<u32>) {
+        self.quantize_levels = levels.map(|levels| max(levels, 2));
+    }
+
+    /// Shape of the waves drawn by [`Plotter::draw`], sine waves by default.
+    pub fn set_shape(&mut self, shape: StrokeShape) {
+        self.shape = shape;
+    }
+
+    /// Number of whole oscillations fitted in every cell, for a finer texture, 1 by default. Each
+    /// gets an equal share of the cell width, rounded down to whole pixels, and any pixels left
+    /// over at the end of the cell are drawn as a flat line. There are never more subwaves than
+    /// the quarter wavelength, and fewer than 1 is treated as 1.
+    pub fn set_subwaves(&mut self, subwaves: u32) {
+        self.subwaves = max(subwaves, 1);
+    }
+
+    /// Whether drawings join the rows with vertical strokes, alternately at the right and left
+    /// edges of the inner canvas, so that the whole image reads as a single line travelled back
+    /// and forth, as with [`Plotter::path_serpentine`]. Off by default.
+    pub fn set_connect_rows(&mut self, connect_rows: bool) {
+        self.connect_rows = connect_rows;
+    }
+
+    /// Fixed wavelength of the oscillations drawn in the cells, in canvas pixels, for the same
+    /// texture whatever the cell size. Rounded to a multiple of four, as waves are made of four
+    /// whole quarters, and capped at the cell width. As many whole oscillations as fit are drawn
+    /// in every cell, from its start, the rest of the cell being a flat line as with
+    /// [`Plotter::set_subwaves`], which this overrides. `None`, the default, fits the number of
+    /// subwaves in every cell instead.
+    pub fn set_wavelength_px(&mut self, wavelength: Option<u32>) {
+        self.wavelength_px = wavelength;
+    }
+
+    /// Whether drawings shift the sine waves of every odd row by half a period, so that the
+    /// crests of one row sit above the troughs of the next for a woven look. Cell centers and
+    /// amplitudes are unchanged. Off by default, and only sine waves are staggered.
+    pub fn set_stagger(&mut self, stagger: bool) {
+        self.stagger = stagger;
+    }
+
+    /// Random wobble of the strokes for a hand-drawn look: every point moves up or down by up to
+    /// `jitter` pixels, without leaving its cell. The same `seed` always gives the same strokes.
+    /// No jitter by default, and negative values count as none.
+    pub fn set_jitter(&mut self, jitter: f64, seed: u64) {
+        self.jitter = jitter.max(0.0);
+        self.seed = seed;
+    }
+
+    /// Low frequency noise added to the amplitudes, so that the waves undulate regardless of the
+    /// image: every amplitude moves by up to `strength` times the largest one, still clamped
+    /// between the floor and the largest amplitude. The noise field is smooth over about `scale`
+    /// cells, and follows the seed given to [`Plotter::set_jitter`]. No noise by default, and
+    /// negative strengths count as none.
+    pub fn set_noise(&mut self, strength: f64, scale: f64) {
+        self.noise_strength = strength.max(0.0);
+        self.noise_scale = scale;
+    }
+
+    /// Number of subwaves in a cell holding a single wave of quarter wavelength `qwave`, and
+    /// their own quarter wavelength, see [`Plotter::set_subwaves`] and
+    /// [`Plotter::set_wavelength_px`].
+    fn subwave_layout(&self, qwave: u32) -> (u32, u32) {
+        if let Some(wavelength) = self.wavelength_px {
+            let sub_qwave = ((wavelength + 2) / 4).clamp(1, max(qwave, 1));
+            return (max(qwave / sub_qwave, 1), sub_qwave);
+        }
+        let subwaves = max(min(self.subwaves, qwave), 1);
+        (subwaves, qwave / subwaves)
+    }
+
+    /// Phase of the sine waves of row `row`, see [`Plotter::set_stagger`].
+    fn stagger_phase(&self, row: u32) -> u32 {
+        if self.stagger && row % 2 == 1 {
+            2
+        } else {
+            0
+        }
+    }
+
+    /// Layout of the cells of row `cell_y`, left to right, with amplitudes up to `amax` following
+    /// the brightness of `grid`, one pixel per cell. Empty past the last row.
+    fn row_layout(&self, grid: &GrayImage, cell_y: u32, amax: u32) -> Vec<CellLayout> {
+        if cell_y >= grid.height() {
+            return Vec::new();
+        }
+        let (cw, ch) = (self.cell_width(), self.cell_height());
+        let (oscillations, quarter_wavelength) = self.subwave_layout(self.quarter_wavelength());
+        // calculate every time to avoid period falling behind
+        let y = self.cell_to_sine_start_y(cell_y) as i32;
+        (0..grid.width())
+            .map(|cell_x| CellLayout {
+                cell_x,
+                cell_y,
+                start: Point::new((cw * cell_x) as i32, y),
+                width: cw,
+                height: ch,
+                amplitude: self.amplitude_of(
+                    grid.get_pixel(cell_x, cell_y).0[0],
+                    cell_x,
+                    cell_y,
+                    amax,
+                ),
+                quarter_wavelength,
+                oscillations,
+                phase: self.stagger_phase(cell_y),
+                jitter: self.jitter,
+            })
+            .collect()
+    }
+
+    /// Same as [`Plotter::row_layout`] for column `cell_x` of the source, bottom to top, in
+    /// coordinates transposed as by [`Transposed`], for [`Plotter::draw_vertical`]. The cells
+    /// hold a single wave of a quarter of the cell height, and are as wide as the cell is.
+    fn column_layout(&self, cell_x: u32, amax: u32) -> Vec<CellLayout> {
+        let (nw, nh) = self.source.dimensions();
+        if cell_x >= nw {
+            return Vec::new();
+        }
+        let (cw, qwave) = (self.cell_width(), self.cell_height() / 4);
+        let (oscillations, quarter_wavelength) = self.subwave_layout(qwave);
+        let x = (cw * cell_x + cw / 2) as i32;
+        (0..nh)
+            .rev()
+            .map(|cell_y| CellLayout {
+                cell_x,
+                cell_y,
+                // calculate every time to avoid period falling behind
+                start: Point::new((self.canvas.ih * (nh - cell_y - 1) / nh) as i32, x),
+                width: 4 * qwave,
+                height: cw,
+                amplitude: self.amplitude(cell_x, cell_y, amax),
+                quarter_wavelength,
+                oscillations,
+                phase: self.stagger_phase(cell_x),
+                jitter: self.jitter,
+            })
+            .collect()
+    }
+
+    /// Amplitude of the wave in a cell, between the floor and `amax`.
+    fn amplitude(&self, x: u32, y: u32, amax: u32) -> u32 {
+        self.amplitude_of(self.source.get_pixel(x, y).0[0], x, y, amax)
+    }
+
+    /// Amplitude of the wave in a cell of the given brightness, see [`Plotter::amplitude`].
+    fn amplitude_of(&self, pixel: u8, x: u32, y: u32, amax: u32) -> u32 {
+        let pixel = self.mapping.factor(min(pixel, self.threshold));
+        let mut pixel = pixel.powf(self.gamma);
+        if let Some(levels) = self.quantize_levels {
+            let steps = (levels - 1) as f64;
+            pixel = (pixel * steps).round() / steps;
+        }
+        let pixel = (255.0 * pixel).round() as u8;
+        let floor = (amax as f64 * self.amplitude_floor) as u32;
+        let mut amplitude = self.amplitude_map.amplitude(pixel, amax);
+        if self.noise_strength > 0.0 {
+            let scale = self.noise_scale.max(f64::EPSILON);
+            let noise = value_noise(self.seed, x as f64 / scale, y as f64 / scale);
+            let nudge = (self.noise_strength * amax as f64 * noise).round() as i64;
+            amplitude = (amplitude as i64 + nudge).clamp(0, amax as i64) as u32;
+        }
+        max(min(amplitude, amax), floor)
+    }
+
+    /// Quarter wavelengths of the oscillations fitting in a cell when encoding brightness as
+    /// frequency. Darker cells get more oscillations, and since the quarter wavelengths always add
+    /// up to that of a single wave, the last oscillation ends exactly on the cell border.
+    fn fm_quarter_wavelengths(&self, x: u32, y: u32) -> Vec<u32> {
+        let qwave = self.quarter_wavelength();
+        let nmax = max(qwave / MIN_FM_QUARTER_WAVELENGTH, 1);
+        let n = 1 + (nmax - 1) * self.amplitude(x, y, 255) / 255;
+        (0..n)
+            .map(|i| qwave / n + u32::from(i < qwave % n))
+            .collect()
+    }
+
+    /// Draw with the thickness the plotter was configured with.
+    pub fn render(&mut self) {
+        self.draw(self.thickness);
+    }
+
+    pub fn draw(&mut self, thickness: u32) {
+        self.draw_with_progress(thickness, |_, _| {});
+    }
+
+    /// Same as [`Plotter::draw`], returning how much work it took.
+    pub fn draw_with_stats(&mut self, thickness: u32) -> RenderStats {
+        self.draw_with_progress(thickness, |_, _| {})
+    }
+
+    /// Same as [`Plotter::draw_with_stats`], calling `on_row(done, total)` after every row of
+    /// cells, e.g. to drive a progress bar. `done` counts the rows drawn so far, from 1 up to
+    /// `total`.
+    pub fn draw_with_progress<F: FnMut(u32, u32)>(
+        &mut self,
+        thickness: u32,
+        on_row: F,
+    ) -> RenderStats {
+        // the image is moved out while drawing, so that the rest of the plotter can be read
+        let mut image = std::mem::take(self.canvas.image_mut());
+        let mut band = Band::new(&self.canvas, &mut image, 0);
+        let rows = 0..self.source.height();
+        let stats = self.draw_rows_onto(&mut band, &self.source, thickness, rows, on_row);
+        *self.canvas.image_mut() = image;
+        stats
+    }
+
+    /// Same as [`Plotter::render`] on a blank canvas, without holding the whole image: it is
+    /// rendered in horizontal bands of `tile_rows` rows of cells, top to bottom, and each band
+    /// is handed to `sink` with the image row it starts at. The first and last bands include the
+    /// border above and below the inner image. Waves straddling two bands are drawn into both,
+    /// every band being drawn with the rows of cells up to [`Plotter::max_amplitude`] pixels past
+    /// its edges, so the bands join seamlessly. [`Plotter::canvas`] is left untouched.
+    pub fn render_tiled(&self, tile_rows: u32, mut sink: impl FnMut(u32, &GrayImage)) {
+        let (nh, ih, oh) = (self.source.height(), self.canvas.ih, self.canvas.oh);
+        let tile_rows = max(tile_rows, 1);
+        // jittered strokes may move anywhere in their cell, and row connectors reach the next row
+        let overlap = max(self.max_amplitude(), self.cell_height()) + 1;
+        let center = |cell_y: u32| self.canvas.fh - 1 - oh - self.cell_to_sine_start_y(cell_y);
+        let edge = |cell_y: u32| match cell_y {
+            0 => 0,
+            y if y == nh => self.canvas.fh,
+            y => oh + y * ih / nh,
+        };
+
+        for first in (0..nh).step_by(tile_rows as usize) {
+            let (top, bottom) = (edge(first), edge(min(first + tile_rows, nh)));
+            let rows: Vec<_> = (0..nh)
+                .filter(|&y| center(y) + overlap >= top && center(y) < bottom + overlap)
+                .collect();
+            let mut image = GrayImage::from_pixel(
+                self.canvas.fw,
+                bottom - top,
+                image::Luma([self.canvas.background()]),
+            );
+            if let (Some(&start), Some(&end)) = (rows.first(), rows.last()) {
+                let mut band = Band::new(&self.canvas, &mut image, top);
+                let rows = start..end + 1;
+                self.draw_rows_onto(&mut band, &self.source, self.thickness, rows, |_, _| {});
+            }
+            sink(top, &image);
+        }
+    }
+
+    /// Same as [`Plotter::draw_with_progress`] onto any drawable laid out like the canvas, only
+    /// drawing the given rows of cells, with the brightness of the cells read from `grid`, one
+    /// pixel per cell, rather than necessarily from the source.
+    fn draw_rows_onto<D: XYDrawable, F: FnMut(u32, u32)>(
+        &self,
+        target: &mut D,
+        grid: &GrayImage,
+        thickness: u32,
+        rows: Range<u32>,
+        mut on_row: F,
+    ) -> RenderStats {
+        let amax = self.effective_max_amplitude(thickness);
+        let (ink, nh) = (target.ink(), grid.height());
+        let mut templates = Templates::new();
+        self.draw_cells(
+            target,
+            rows,
+            |cell_y| self.row_layout(grid, cell_y, amax),
+            |_| (ink, thickness),
+            |canvas, cell, thickness| self.draw_cell(canvas, cell, thickness, &mut templates),
+            |cell_y| on_row(cell_y + 1, nh),
+        )
+    }
+
+    /// Draw the given rows of cells onto `target`, as laid out row by row by `layout`, e.g.
+    /// [`Plotter::row_layout`]: every cell with `draw`, in the ink and thickness given by
+    /// `style`, through [`Jittered`]. Jitter is seeded row by row, so every row comes out the
+    /// same whichever other rows are drawn. Rows are then joined to the next one if set, see
+    /// [`Plotter::set_connect_rows`], and `on_row` is called with the row just drawn. This is the
+    /// one loop over the cells that every drawing mode goes through.
+    fn draw_cells<D: XYDrawable>(
+        &self,
+        target: &mut D,
+        rows: Range<u32>,
+        layout: impl Fn(u32) -> Vec<CellLayout>,
+        style: impl Fn(&CellLayout) -> (D::Value, u32),
+        mut draw: impl FnMut(&mut Jittered<'_, Inked<'_, Counted<'_, D>>>, &CellLayout, u32),
+        mut on_row: impl FnMut(u32),
+    ) -> RenderStats {
+        let mut stats = RenderStats {
+            min_amplitude: u32::MAX,
+            ..Default::default()
+        };
+        let mut counted = Counted {
+            canvas: target,
+            writes: 0,
+        };
+        let mut following = None;
+
+        for row in rows {
+            let cells = following.take().unwrap_or_else(|| layout(row));
+            let mut rng = StdRng::seed_from_u64(self.seed ^ (row as u64).wrapping_mul(ROW_SEED));
+            for cell in cells.iter() {
+                stats.waves += 1;
+                stats.min_amplitude = min(stats.min_amplitude, cell.amplitude);
+                stats.max_amplitude = max(stats.max_amplitude, cell.amplitude);
+                let (ink, thickness) = style(cell);
+                let mut inked = Inked {
+                    canvas: &mut counted,
+                    ink,
+                };
+                let mut canvas = Jittered {
+                    canvas: &mut inked,
+                    rng: &mut rng,
+                    jitter: cell.jitter,
+                    center_y: cell.start.y,
+                    half_height: (cell.height / 2) as i32,
+                };
+                draw(&mut canvas, cell, thickness);
+            }
+            if self.connect_rows {
+                let next = layout(row + 1);
+                if let Some((cell, from, to)) = row_connector(row, &cells, &next) {
+                    let (ink, thickness) = style(cell);
+                    let center = Point::new(from.x, (from.y + to.y) / 2);
+                    let extent = (from.y - to.y).unsigned_abs().div_ceil(2);
+                    let mut canvas = Inked {
+                        canvas: &mut counted,
+                        ink,
+                    };
+                    VerticalLine::new(center, extent).draw_thick(&mut canvas, thickness);
+                }
+                following = Some(next);
+            }
+            on_row(row);
+        }
+        stats.pixels_drawn = counted.writes;
+        stats
+    }
+
+    /// Draw the wave of `cell` in the configured shape. Sine waves of the same layout only differ
+    /// by where they start, so their pixels are traced once into `templates`, shared by all the
+    /// cells of a drawing, then moved to every cell.
+    fn draw_cell(
+        &self,
+        canvas: &mut impl XYDrawable,
+        cell: &CellLayout,
+        thickness: u32,
+        templates: &mut Templates,
+    ) {
+        let ink = canvas.ink();
+        if cell.amplitude == 0 {
+            cell.flat_line().draw_thick(canvas, thickness);
+        } else if self.shape != StrokeShape::Sine {
+            for wave in cell.waves(self.shape) {
+                wave.draw_thick(canvas, thickness);
+            }
+            for point in cell.tail() {
+                canvas.set_horizontal_line(&point, ink, thickness);
+            }
+        } else {
+            let key = (
+                cell.amplitude,
+                cell.quarter_wavelength,
+                cell.oscillations,
+                cell.phase,
+                cell.width,
+            );
+            let template = templates.entry(key).or_insert_with(|| {
+                let origin = CellLayout {
+                    start: Point::origin(),
+                    ..*cell
+                };
+                origin.sine_points()
+            });
+            for point in template.iter() {
+                canvas.set_horizontal_line(&(*point + cell.start), ink, thickness);
+            }
+        }
+    }
+
+    /// Same as [`Plotter::draw_cell`] with antialiased strokes, see
+    /// [`Drawable::draw_thick_antialiased`]. Flat lines are horizontal and left as is.
+    fn draw_cell_antialiased<D: XYDrawable>(
+        &self,
+        canvas: &mut D,
+        cell: &CellLayout,
+        thickness: u32,
+    ) where
+        D::Value: Gray,
+    {
+        let ink = canvas.ink();
+        if cell.amplitude == 0 {
+            cell.flat_line().draw_thick(canvas, thickness);
+            return;
+        }
+        for wave in cell.waves(self.shape) {
+            wave.draw_thick_antialiased(canvas, thickness);
+        }
+        for point in cell.tail() {
+            canvas.set_horizontal_line(&point, ink, thickness);
+        }
+    }
+
+    /// Stroke thickness of a cell for [`Plotter::draw_variable_thickness`], from 1 for white up
+    /// to `max_thickness` for black.
+    fn cell_thickness(&self, x: u32, y: u32, max_thickness: u32) -> u32 {
+        let darkness = 255 - self.get_pixel_as_u32(x, y);
+        1 + max_thickness.saturating_sub(1) * darkness / 255
+    }
+
+    /// Same as [`Plotter::draw`], with strokes getting thicker as well as taller in darker cells,
+    /// from 1 pixel for white up to `max_thickness` for black. Amplitudes leave room for the
+    /// thickest strokes, see [`Plotter::effective_max_amplitude`].
+    pub fn draw_variable_thickness(&mut self, max_thickness: u32) {
+        // the image is moved out while drawing, as in Plotter::draw_with_progress
+        let mut image = std::mem::take(self.canvas.image_mut());
+        let amax = self.effective_max_amplitude(max_thickness);
+        let ink = self.canvas.ink;
+        let mut templates = Templates::new();
+        self.draw_cells(
+            &mut Band::new(&self.canvas, &mut image, 0),
+            0..self.source.height(),
+            |cell_y| self.row_layout(&self.source, cell_y, amax),
+            |cell| {
+                (
+                    ink,
+                    self.cell_thickness(cell.cell_x, cell.cell_y, max_thickness),
+                )
+            },
+            |canvas, cell, thickness| self.draw_cell(canvas, cell, thickness, &mut templates),
+            |_| {},
+        );
+        *self.canvas.image_mut() = image;
+    }
+
+    /// Same as [`Plotter::draw`] onto any drawable, e.g. a canvas shared with other plotters to
+    /// layer several drawings, with strokes written as `value` rather than in the drawable's ink.
+    /// Cells are laid out as on this plotter's own canvas, starting at the origin of the
+    /// drawable, see [`crate::canvas::Translated`] to move them.
+    pub fn draw_onto<D: XYDrawable>(&self, canvas: &mut D, thickness: u32, value: D::Value) {
+        let mut canvas = Inked { canvas, ink: value };
+        let rows = 0..self.source.height();
+        self.draw_rows_onto(&mut canvas, &self.source, thickness, rows, |_, _| {});
+    }
+
+    /// Color print look, as if from a misregistered risograph: every channel of the source
+    /// colors is drawn as its own layer of waves, red strokes for the red channel and so on, on a
+    /// white color canvas of the same size as [`Plotter::canvas`]. Layers multiply where they
+    /// overlap, so all three together give black. The red layer is moved by `offset` pixels
+    /// left and up, the blue one by as much right and down, while the green one stays in place.
+    /// Uses the configured thickness.
+    pub fn draw_channel_separation(&self, offset: i32) -> RgbCanvas {
+        let mut canvas = RgbCanvas::new(
+            [self.canvas.fh, self.canvas.fw],
+            [self.canvas.ih, self.canvas.iw],
+            Rgb([255, 255, 255]),
+        );
+        for channel in 0..3 {
+            let grid = GrayImage::from_fn(self.source.width(), self.source.height(), |x, y| {
+                image::Luma([self.color(x, y).0[channel]])
+            });
+            let mut tint = [0; 3];
+            tint[channel] = 255;
+            let shift = (channel as i32 - 1) * offset;
+            let mut layer = Multiplied(&mut canvas);
+            let mut layer = Translated(&mut layer, Point::new(shift, -shift));
+            let mut layer = Inked {
+                canvas: &mut layer,
+                ink: Rgb(tint),
+            };
+            let rows = 0..grid.height();
+            self.draw_rows_onto(&mut layer, &grid, self.thickness, rows, |_, _| {});
+        }
+        canvas
+    }
+
+    /// Same as [`Plotter::draw`], with the boundaries of the cells drawn over the waves in the
+    /// given gray level, see [`Canvas::draw_grid`].
+    pub fn draw_with_grid(&mut self, thickness: u32, value: u8) {
+        self.draw(thickness);
+        let (cw, ch) = (self.cell_width(), self.cell_height());
+        self.canvas.draw_grid(cw, ch, value);
+    }
+
+    /// Same as [`Plotter::draw`] with antialiased strokes. The strokes are as thick as with
+    /// [`Plotter::draw`] and their insides pure ink, only their outer edges fade into the
+    /// background, see [`Drawable::draw_thick_antialiased`]. Flat waves are horizontal and left
+    /// as is. Jitter is not applied, as the edges are shaded pixel by pixel around the ideal
+    /// strokes, which it would tear them away from.
+    pub fn draw_antialiased(&mut self, thickness: u32) {
+        // the image is moved out while drawing, as in Plotter::draw_with_progress
+        let mut image = std::mem::take(self.canvas.image_mut());
+        let amax = self.effective_max_amplitude(thickness);
+        let ink = self.canvas.ink;
+        self.draw_cells(
+            &mut Band::new(&self.canvas, &mut image, 0),
+            0..self.source.height(),
+            |cell_y| {
+                let cells = self.row_layout(&self.source, cell_y, amax).into_iter();
+                cells
+                    .map(|cell| CellLayout {
+                        jitter: 0.0,
+                        ..cell
+                    })
+                    .collect()
+            },
+            |_| (ink, thickness),
+            |canvas, cell, thickness| self.draw_cell_antialiased(canvas, cell, thickness),
+            |_| {},
+        );
+        *self.canvas.image_mut() = image;
+    }
+
+    /// Mean and variance of the brightness of the full resolution source, from 0 to 1, over a
+    /// `1 / split` by `1 / split` part of a cell, the `sub_x`-th across and `sub_y`-th down.
+    fn region_stats(
+        &self,
+        cell_x: u32,
+        cell_y: u32,
+        split: u32,
+        sub_x: u32,
+        sub_y: u32,
+    ) -> (f64, f64) {
+        let (w, h) = self.original().dimensions();
+        let (nw, nh) = (self.source.width() * split, self.source.height() * split);
+        let (x, y) = (cell_x * split + sub_x, cell_y * split + sub_y);
+        let (j0, i0) = (x * w / nw, y * h / nh);
+        let (j1, i1) = (max((x + 1) * w / nw, j0 + 1), max((y + 1) * h / nh, i0 + 1));
+        let values = (i0..min(i1, h))
+            .flat_map(|i| (j0..min(j1, w)).map(move |j| (j, i)))
+            .map(|(j, i)| self.original().get_pixel(j, i).0[0] as f64 / 255.0)
+            .collect::<Vec<_>>();
+        let n = max(values.len(), 1) as f64;
+        let mean = values.iter().sum::<f64>() / n;
+        let variance = values.iter().map(|v| (v - mean) * (v - mean)).sum::<f64>() / n;
+        (mean, variance)
+    }
+
+    /// Whether [`Plotter::draw_adaptive`] splits a cell, from the variance of its brightness.
+    fn is_detailed(&self, cell_x: u32, cell_y: u32, threshold: f64) -> bool {
+        self.region_stats(cell_x, cell_y, 1, 0, 0).1 > threshold
+    }
+
+    /// Same as [`Plotter::render`], with more waves where the source has more detail: cells whose
+    /// brightness varies more than `threshold` over the full resolution source are split into
+    /// 2 x 2 smaller cells, each with a wave of half the wavelength and amplitude. The variance
+    /// is that of brightness from 0 to 1, so at most 0.25 for pure black and white, and around
+    /// 0.01 already marks visible texture. Split cells are joined to their neighbours by vertical
+    /// strokes at their edges, and their amplitudes follow the source directly, without
+    /// equalization or quantization.
+    pub fn draw_adaptive(&mut self, threshold: f64) -> RenderStats {
+        // the image is moved out while drawing, as in Plotter::draw_with_progress
+        let mut image = std::mem::take(self.canvas.image_mut());
+        let thickness = self.thickness;
+        let amax = self.effective_max_amplitude(thickness);
+        let ink = self.canvas.ink;
+        let mut templates = Templates::new();
+        let stats = self.draw_cells(
+            &mut Band::new(&self.canvas, &mut image, 0),
+            0..self.source.height(),
+            |cell_y| {
+                let cells = self.row_layout(&self.source, cell_y, amax).into_iter();
+                cells
+                    .flat_map(
+                        |cell| match self.is_detailed(cell.cell_x, cell.cell_y, threshold) {
+                            true => self.split_cell(&cell),
+                            false => vec![cell],
+                        },
+                    )
+                    .collect()
+            },
+            |_| (ink, thickness),
+            |canvas, cell, thickness| {
+                self.draw_cell(canvas, cell, thickness, &mut templates);
+                // the top halves of split cells are joined to the bottom ones, and through the
+                // center line to the neighbours, at their outer edges
+                let center_y = self.cell_to_sine_start_y(cell.cell_y) as i32;
+                if cell.start.y > center_y {
+                    let left = (self.cell_width() * cell.cell_x) as i32;
+                    let edge = if cell.start.x == left {
+                        left
+                    } else {
+                        cell.start.x + cell.width as i32
+                    };
+                    let extent = (cell.start.y - center_y) as u32;
+                    VerticalLine::new(Point::new(edge, center_y), extent)
+                        .draw_thick(canvas, thickness);
+                }
+            },
+            |_| {},
+        );
+        *self.canvas.image_mut() = image;
+        stats
+    }
+
+    /// The 2 x 2 smaller cells [`Plotter::draw_adaptive`] splits `cell` into, top row first, each
+    /// half as high with a wave of half the amplitude and wavelength. The second cell of a row
+    /// runs to the end of the cell, whatever whole quarters leave over being flat. Cells too
+    /// narrow for two waves are left whole.
+    fn split_cell(&self, cell: &CellLayout) -> Vec<CellLayout> {
+        // split cells hold two waves of whole quarters per row, and any pixels they leave flat
+        let sub_qwave = cell.width / 8;
+        if sub_qwave == 0 {
+            return vec![*cell];
+        }
+        let (oscillations, quarter_wavelength) = self.subwave_layout(sub_qwave);
+        let sub_amax = (self.max_amplitude() / 2).saturating_sub(self.thickness);
+        let (cell_x, cell_y) = (cell.cell_x, cell.cell_y);
+        (0..2)
+            .flat_map(|sub_y| (0..2).map(move |sub_x| (sub_x, sub_y)))
+            .map(|(sub_x, sub_y)| {
+                // the first sub row is the top one, with the larger Y
+                let y = cell.start.y + (cell.height / 4) as i32 * (1 - 2 * sub_y as i32);
+                let x = cell.start.x + (4 * sub_qwave * sub_x) as i32;
+                let (mean, _) = self.region_stats(cell_x, cell_y, 2, sub_x, sub_y);
+                let pixel = (255.0 * mean).round() as u8;
+                CellLayout {
+                    start: Point::new(x, y),
+                    width: if sub_x == 0 {
+                        4 * sub_qwave
+                    } else {
+                        cell.width - 4 * sub_qwave
+                    },
+                    height: cell.height / 2,
+                    amplitude: self.amplitude_of(pixel, cell_x, cell_y, sub_amax),
+                    quarter_wavelength,
+                    oscillations,
+                    ..*cell
+                }
+            })
+            .collect()
+    }
+
+    /// Same as [`Plotter::draw`] on a white color canvas of the same size, each wave drawn in the
+    /// average color of its cell rather than black. Amplitudes still follow brightness.
+    pub fn draw_colored(&self, thickness: u32) -> RgbCanvas {
+        let mut canvas = RgbCanvas::new(
+            [self.canvas.fh, self.canvas.fw],
+            [self.canvas.ih, self.canvas.iw],
+            Rgb([255, 255, 255]),
+        );
+        let amax = self.effective_max_amplitude(thickness);
+        let mut templates = Templates::new();
+        self.draw_cells(
+            &mut canvas,
+            0..self.source.height(),
+            |cell_y| self.row_layout(&self.source, cell_y, amax),
+            |cell| (self.color(cell.cell_x, cell.cell_y), thickness),
+            |canvas, cell, thickness| self.draw_cell(canvas, cell, thickness, &mut templates),
+            |_| {},
+        );
+        canvas
+    }
+
+    /// Same as [`Plotter::draw`], on top of a faint copy of the source image stretched over the
+    /// inner canvas, as a guide to the eye. The copy is blended towards white, `opacity` 0 leaving
+    /// it out entirely and 1 showing the source as is. Clamped to [0, 1].
+    pub fn draw_with_underlay(&mut self, thickness: u32, opacity: f64) {
+        let opacity = opacity.clamp(0.0, 1.0);
+        if opacity > 0.0 {
+            let (iw, ih) = (self.canvas.iw, self.canvas.ih);
+            let underlay = imageops::resize(self.original(), iw, ih, FilterType::Triangle);
+            for (j, i, pixel) in underlay.enumerate_pixels() {
+                let value = 255.0 - opacity * (255 - pixel.0[0]) as f64;
+                self.canvas
+                    .set_xy(j as i32, (ih - 1 - i) as i32, value.round() as u8);
+            }
+        }
+        self.draw(thickness);
+    }
+
+    /// Amplitude at horizontal position `x` of row `cell_y`, interpolated linearly between the
+    /// centers of the two nearest cells.
+    fn continuous_amplitude(&self, x: f64, cell_y: u32, amax: u32) -> f64 {
+        let last = self.source.width() - 1;
+        let t = (x / self.cell_width() as f64 - 0.5).max(0.0);
+        let left = min(t.floor() as u32, last);
+        let right = min(left + 1, last);
+        let fraction = (t - left as f64).min(1.0);
+        let a0 = self.amplitude(left, cell_y, amax) as f64;
+        let a1 = self.amplitude(right, cell_y, amax) as f64;
+        a0 + (a1 - a0) * fraction
+    }
+
+    /// Alternative to [`Plotter::draw`] drawing each row as a single wave, whose phase carries on
+    /// from cell to cell and whose amplitude varies smoothly between cell centers, avoiding
+    /// seams where neighbouring cells differ. The wave is sampled at every column and
+    /// consecutive samples are joined vertically.
+    pub fn draw_continuous(&mut self, thickness: u32) {
+        let width = self.cell_width() * self.source.width();
+        let step = PI / (2.0 * max(self.quarter_wavelength(), 1) as f64);
+        let amax = self.effective_max_amplitude(thickness);
+        let ink = self.canvas.ink;
+
+        for cell_y in 0..self.source.height() {
+            let y0 = self.cell_to_sine_start_y(cell_y) as f64;
+            let mut phase = 0.0;
+            let mut previous = y0.round() as i32;
+            for x in 0..=width {
+                let a = self.continuous_amplitude(x as f64, cell_y, amax);
+                let y = (y0 + a * f64::sin(phase)).round() as i32;
+                for y in min(previous, y)..=max(previous, y) {
+                    let point = Point::new(x as i32, y);
+                    self.canvas.set_horizontal_line(&point, ink, thickness);
+                }
+                previous = y;
+                phase += step;
+            }
+        }
+    }
+
+    /// Same as [`Plotter::draw`], but with triangle waves instead of sine waves.
+    pub fn draw_triangle(&mut self, thickness: u32) {
+        self.draw_shape(StrokeShape::Triangle, thickness);
+    }
+
+    /// Same as [`Plotter::draw`], but with sawtooth waves instead of sine waves.
+    pub fn draw_sawtooth(&mut self, thickness: u32) {
+        self.draw_shape(StrokeShape::Sawtooth, thickness);
+    }
+
+    /// Same as [`Plotter::draw`] with waves of the given shape, whatever the configured one.
+    fn draw_shape(&mut self, shape: StrokeShape, thickness: u32) {
+        let configured = std::mem::replace(&mut self.shape, shape);
+        self.draw(thickness);
+        self.shape = configured;
+    }
+
+    /// Experimental alternative to [`Plotter::render`], with a Lissajous figure of frequencies
+    /// `a` and `b` filling every oscillation of a cell instead of a wave. Brightness sets how
+    /// open the figures are: the phase goes from 0 for white, e.g. a flat diagonal for a 1:1
+    /// figure, to a quarter turn for black, e.g. an ellipse.
+    pub fn draw_lissajous(&mut self, a: u32, b: u32) {
+        // the image is moved out while drawing, as in Plotter::draw_with_progress
+        let mut image = std::mem::take(self.canvas.image_mut());
+        let thickness = self.thickness;
+        let amax = self.effective_max_amplitude(thickness);
+        let height = self.max_amplitude().saturating_sub(thickness);
+        let ink = self.canvas.ink;
+        self.draw_cells(
+            &mut Band::new(&self.canvas, &mut image, 0),
+            0..self.source.height(),
+            |cell_y| self.row_layout(&self.source, cell_y, amax),
+            |_| (ink, thickness),
+            |canvas, cell, thickness| {
+                let phase = PI / 2.0 * cell.amplitude as f64 / max(amax, 1) as f64;
+                let qwave = cell.quarter_wavelength;
+                let half_width = (2 * qwave).saturating_sub(thickness + 1);
+                // figures do not run along the center line, so there is no flat tail to draw
+                for start in cell.starts() {
+                    let center = start + Point::new(2 * qwave as i32, 0);
+                    Lissajous::new(center, half_width, height, a, b, phase)
+                        .draw_thick(canvas, thickness);
+                }
+            },
+            |_| {},
+        );
+        *self.canvas.image_mut() = image;
+    }
+
+    /// Antialiased alternative to [`Plotter::draw`]: the waves are drawn on a canvas `factor`
+    /// times larger, with all geometry scaled to match, which is then averaged back down.
+    pub fn draw_supersampled(&mut self, thickness: u32, factor: u32) {
+        // the image is moved out while drawing, as in Plotter::draw_with_progress
+        let mut image = std::mem::take(self.canvas.image_mut());
+        self.supersample_onto(&self.canvas, &mut image, thickness, factor);
+        *self.canvas.image_mut() = image;
+    }
+
+    /// See [`Plotter::draw_supersampled`], drawing onto `image`, that of `canvas` or of any
+    /// canvas of the same size.
+    fn supersample_onto(
+        &self,
+        canvas: &Canvas,
+        image: &mut GrayImage,
+        thickness: u32,
+        factor: u32,
+    ) {
+        let factor = max(factor, 1);
+        let (fw, fh) = image.dimensions();
+        let mut large = imageops::resize(image, fw * factor, fh * factor, FilterType::Nearest);
+        // same stroke width, 2t + 1 pixels, once scaled
+        let scaled_thickness = ((2 * thickness + 1) * factor - 1) / 2;
+        let amax = self.effective_max_amplitude(thickness);
+        let mut templates = Templates::new();
+        self.draw_cells(
+            &mut Band::upscaled(canvas, &mut large, factor),
+            0..self.source.height(),
+            |cell_y| {
+                let cells = self.row_layout(&self.source, cell_y, amax).into_iter();
+                cells.map(|cell| cell.upscaled(factor)).collect()
+            },
+            |_| (canvas.ink, scaled_thickness),
+            |canvas, cell, thickness| self.draw_cell(canvas, cell, thickness, &mut templates),
+            |_| {},
+        );
+        *image = imageops::resize(&large, fw, fh, FilterType::Triangle);
+    }
+
+    /// Poster-like rendering in two colors, with the configured thickness: waves in `shadow` on a
+    /// `highlight` background. Edges are antialiased, blending from one color to the other.
+    pub fn draw_duotone(&self, shadow: Rgb<u8>, highlight: Rgb<u8>) -> RgbCanvas {
+        let (fh, fw, ih, iw) = (
+            self.canvas.fh,
+            self.canvas.fw,
+            self.canvas.ih,
+            self.canvas.iw,
+        );
+        let mut gray = Canvas::new([fh, fw], [ih, iw]);
+        let mut image = std::mem::take(gray.image_mut());
+        self.supersample_onto(&gray, &mut image, self.thickness, DUOTONE_SUPERSAMPLING);
+        *gray.image_mut() = image;
+
+        let mut canvas = RgbCanvas::new([fh, fw], [ih, iw], highlight);
+        for x in 0..iw as i32 {
+            for y in 0..ih as i32 {
+                let t = gray.get_xy(x, y) as f64 / 255.0;
+                let (s, h) = (shadow.0, highlight.0);
+                let color = [0, 1, 2]
+                    .map(|c| (s[c] as f64 + (h[c] as f64 - s[c] as f64) * t).round() as u8);
+                canvas.set_xy(x, y, Rgb(color));
+            }
+        }
+        canvas
+    }
+
+    /// Frames of an animation where the waves grow from flat lines to the full rendering, frame
+    /// `i` (counting from 1) having its amplitudes scaled by `i / steps`. Each frame is drawn on a
+    /// blank canvas with the configured thickness, leaving [`Plotter::canvas`] untouched.
+    pub fn render_frames(&self, steps: usize) -> Vec<GrayImage> {
+        let amax = self.effective_max_amplitude(self.thickness);
+        let mut canvas = self.canvas.clone();
+        let ink = canvas.ink;
+        let mut templates = Templates::new();
+        (1..=steps as u32)
+            .map(|step| {
+                canvas.clear();
+                self.draw_cells(
+                    &mut canvas,
+                    0..self.source.height(),
+                    |cell_y| {
+                        let cells = self.row_layout(&self.source, cell_y, amax).into_iter();
+                        cells
+                            .map(|cell| CellLayout {
+                                amplitude: cell.amplitude * step / steps as u32,
+                                ..cell
+                            })
+                            .collect()
+                    },
+                    |_| (ink, self.thickness),
+                    |canvas, cell, thickness| {
+                        self.draw_cell(canvas, cell, thickness, &mut templates)
+                    },
+                    |_| {},
+                );
+                canvas.image().clone()
+            })
+            .collect()
+    }
+
+    /// Alternative to [`Plotter::draw`] where the waves travel vertically, in columns, and their
+    /// amplitude is horizontal. Each cell is drawn as a wave on a transposed canvas, so the roles
+    /// of cell width and height are swapped, and columns take the place of rows for every
+    /// setting, e.g. staggering every odd column.
+    pub fn draw_vertical(&mut self, thickness: u32) {
+        // the image is moved out while drawing, as in Plotter::draw_with_progress
+        let mut image = std::mem::take(self.canvas.image_mut());
+        let amax = (self.cell_width() as f64 * self.amplitude_ratio / 2.0) as u32;
+        let ink = self.canvas.ink;
+        let mut templates = Templates::new();
+        self.draw_cells(
+            &mut Transposed(&mut Band::new(&self.canvas, &mut image, 0)),
+            0..self.source.width(),
+            |cell_x| self.column_layout(cell_x, amax),
+            |_| (ink, thickness),
+            |canvas, cell, thickness| self.draw_cell(canvas, cell, thickness, &mut templates),
+            |_| {},
+        );
+        *self.canvas.image_mut() = image;
+    }
+
+    /// Vector counterpart of [`Plotter::draw`], rendering the waves as an SVG document with one
+    /// path per row of cells, and one more for every join between rows if connected, see
+    /// [`Plotter::set_connect_rows`]. Every quarter of a sine wave is approximated by a cubic
+    /// Bézier segment, whatever the configured shape, and jitter, a raster effect, is not
+    /// applied. The view box matches the inner canvas, with the Y axis flipped as in
+    /// [`crate::canvas::XYDrawable::set_xy`], and `thickness` is the stroke width in pixels.
+    pub fn to_svg(&self, thickness: f64) -> String {
+        let amax = self.effective_max_amplitude(self.thickness);
+        let ih = self.canvas.ih as f64;
+        let flip = |y: f64| ih - 1.0 - y;
+        let nh = self.source.height();
+
+        let mut svg = String::new();
+        writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {w} {h}" width="{w}" height="{h}">"#,
+            w = self.canvas.iw,
+            h = self.canvas.ih,
+        )
+        .unwrap();
+
+        let mut cells = self.row_layout(&self.source, 0, amax);
+        for cell_y in 0..nh {
+            let y = self.cell_to_sine_start_y(cell_y);
+            write!(svg, r#"<path d="M 0 {}"#, flip(y as f64)).unwrap();
+            for cell in cells.iter() {
+                let (a, qw) = (cell.amplitude, cell.quarter_wavelength);
+                for start in cell.starts() {
+                    let sine = Sine::with_phase(start, a, qw, cell.phase);
+                    for [(x1, y1), (x2, y2), (x3, y3)] in sine.cubic_beziers() {
+                        write!(
+                            svg,
+                            " C {} {}, {} {}, {} {}",
+                            x1,
+                            flip(y1),
+                            x2,
+                            flip(y2),
+                            x3,
+                            flip(y3)
+                        )
+                        .unwrap();
+                    }
+                }
+                if let Some(end) = cell.tail().last() {
+                    write!(svg, " L {} {}", end.x, flip(end.y as f64)).unwrap();
+                }
+            }
+            writeln!(
+                svg,
+                r#"" fill="none" stroke="black" stroke-width="{}"/>"#,
+                thickness
+            )
+            .unwrap();
+
+            let next = self.row_layout(&self.source, cell_y + 1, amax);
+            let connector = row_connector(cell_y, &cells, &next).filter(|_| self.connect_rows);
+            if let Some((_, from, to)) = connector {
+                writeln!(
+                    svg,
+                    r#"<path d="M {} {} V {}" fill="none" stroke="black" stroke-width="{}"/>"#,
+                    from.x,
+                    flip(from.y as f64),
+                    flip(to.y as f64),
+                    thickness
+                )
+                .unwrap();
+            }
+            cells = next;
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Every wave of [`Plotter::draw`] as a single path, suited to pen plotters. Rows are
+    /// travelled in alternating directions, starting from the top left, with waves mirrored
+    /// horizontally on the way back, and the end of each row is joined vertically to the start
+    /// of the next one, whether or not rows are connected when drawing. Consecutive points are
+    /// never more than a cell width apart. The waves are sine waves whatever the configured
+    /// shape, and jitter, a raster effect, is not applied.
+    pub fn path_serpentine(&self) -> Vec<Point> {
+        let amax = self.effective_max_amplitude(self.thickness);
+        let mut path: Vec<Point> = Vec::new();
+
+        for cell_y in 0..self.source.height() {
+            let cells = self.row_layout(&self.source, cell_y, amax);
+            let mut waves: Vec<Vec<Point>> = cells.iter().map(CellLayout::sine_points).collect();
+            if cell_y % 2 == 1 {
+                // mirror every wave within its own span, so that it travels towards -X
+                waves.reverse();
+                for (cell, wave) in cells.iter().rev().zip(waves.iter_mut()) {
+                    let mirror = 2 * cell.start.x + cell.width as i32;
+                    for point in wave.iter_mut() {
+                        point.x = mirror - point.x;
+                    }
+                }
+            }
+            let row: Vec<Point> = waves.into_iter().flatten().collect();
+
+            // join the end of the previous row vertically to the start of this one
+            if let (Some(end), Some(start)) = (path.last().copied(), row.first()) {
+                let step = (start.y - end.y).signum();
+                let mut y = end.y + step;
+                while y != start.y {
+                    path.push(Point::new(end.x, y));
+                    y += step;
+                }
+            }
+            path.extend(row);
+        }
+        path.dedup();
+        path
+    }
+
+    /// SVG counterpart of [`Plotter::path_serpentine`], a single polyline with the same view box
+    /// and stroke width as [`Plotter::to_svg`].
+    pub fn to_svg_serpentine(&self, thickness: f64) -> String {
+        let ih = self.canvas.ih as i32;
+
+        let mut svg = String::new();
+        writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {w} {h}" width="{w}" height="{h}">"#,
+            w = self.canvas.iw,
+            h = self.canvas.ih,
+        )
+        .unwrap();
+
+        svg.push_str(r#"<polyline points=""#);
+        for (i, point) in self.path_serpentine().iter().enumerate() {
+            let separator = if i == 0 { "" } else { " " };
+            write!(svg, "{}{},{}", separator, point.x, ih - 1 - point.y).unwrap();
+        }
+        writeln!(
+            svg,
+            r#"" fill="none" stroke="black" stroke-width="{}"/>"#,
+            thickness
+        )
+        .unwrap();
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// G-code for a pen plotter tracing [`Plotter::path_serpentine`], in millimeters (see
+    /// [`Plotter::set_mm_per_pixel`]) with the Y axis pointing down as in the image. The pen is
+    /// moved to `pen_up_z` for travel and to `pen_down_z` for drawing, at `feed` mm/min. The path
+    /// is a single stroke, so the pen is lowered once and only lifted again at the end.
+    pub fn to_gcode(&self, feed: f64, pen_up_z: f64, pen_down_z: f64) -> String {
+        let ih = self.canvas.ih as i32;
+        let mm = |point: &Point| {
+            (
+                point.x as f64 * self.mm_per_pixel,
+                (ih - 1 - point.y) as f64 * self.mm_per_pixel,
+            )
+        };
+
+        let mut gcode = String::new();
+        writeln!(gcode, "G21").unwrap();
+        writeln!(gcode, "G90").unwrap();
+        writeln!(gcode, "G0 Z{:.3}", pen_up_z).unwrap();
+        if let Some((first, rest)) = self.path_serpentine().split_first() {
+            let (x, y) = mm(first);
+            writeln!(gcode, "G0 X{:.3} Y{:.3}", x, y).unwrap();
+            writeln!(gcode, "G0 Z{:.3}", pen_down_z).unwrap();
+            for point in rest {
+                let (x, y) = mm(point);
+                writeln!(gcode, "G1 X{:.3} Y{:.3} F{:.0}", x, y, feed).unwrap();
+            }
+            writeln!(gcode, "G0 Z{:.3}", pen_up_z).unwrap();
+        }
+        gcode
+    }
+
+    /// Frequency modulated alternative to [`Plotter::draw`]: all waves have the maximum amplitude,
+    /// while darker cells get more oscillations. Brightness sets the wavelength here, so
+    /// [`Plotter::set_subwaves`] and [`Plotter::set_wavelength_px`] do not apply.
+    pub fn draw_fm(&mut self, thickness: u32) {
+        // the image is moved out while drawing, as in Plotter::draw_with_progress
+        let mut image = std::mem::take(self.canvas.image_mut());
+        let amax = self.effective_max_amplitude(thickness);
+        let ink = self.canvas.ink;
+        let mut templates = Templates::new();
+        self.draw_cells(
+            &mut Band::new(&self.canvas, &mut image, 0),
+            0..self.source.height(),
+            |cell_y| self.row_layout(&self.source, cell_y, amax),
+            |_| (ink, thickness),
+            |canvas, cell, thickness| {
+                let mut start = cell.start;
+                for qwave in self.fm_quarter_wavelengths(cell.cell_x, cell.cell_y) {
+                    let oscillation = CellLayout {
+                        start,
+                        width: 4 * qwave,
+                        amplitude: amax,
+                        quarter_wavelength: qwave,
+                        oscillations: 1,
+                        ..*cell
+                    };
+                    self.draw_cell(canvas, &oscillation, thickness, &mut templates);
+                    start.x += 4 * qwave as i32;
+                }
+            },
+            |_| {},
+        );
+        *self.canvas.image_mut() = image;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::canvas::XYDrawable;
+
+    #[test]
+    #[ignore = "visual check"]
+    fn logo() {
+        let mut plotter = Plotter::new(50, 50, "tests/lincoln.jpeg", 100, 255).unwrap();
+        plotter.draw(4);
+        plotter.canvas.save("tests/lincoln_sine.jpg").unwrap();
+    }
+
+    #[test]
+    #[ignore = "visual check"]
+    fn logo_fm() {
+        let mut plotter = Plotter::new(50, 50, "tests/lincoln.jpeg", 100, 255).unwrap();
+        plotter.draw_fm(2);
+        plotter.canvas.save("tests/lincoln_sine_fm.jpg").unwrap();
+    }
+
+    #[test]
+    #[ignore = "visual check"]
+    fn logo_antialiased() {
+        let mut plotter = Plotter::new(50, 50, "tests/lincoln.jpeg", 100, 255).unwrap();
+        plotter.draw_antialiased(4);
+        plotter
+            .canvas
+            .save("tests/lincoln_sine_antialiased.jpg")
+            .unwrap();
+    }
+
+    #[test]
+    #[ignore = "visual check"]
+    fn logo_channel_separation() {
+        let mut plotter = Plotter::new(50, 50, "tests/lincoln.jpeg", 100, 255).unwrap();
+        plotter.thickness = 2;
+        let canvas = plotter.draw_channel_separation(6);
+        canvas.save("tests/lincoln_sine_channels.jpg").unwrap();
+    }
+
+    #[test]
+    #[ignore = "visual check"]
+    fn logo_triangle() {
+        let mut plotter = Plotter::new(50, 50, "tests/lincoln.jpeg", 100, 255).unwrap();
+        plotter.draw_triangle(4);
+        plotter.canvas.save("tests/lincoln_triangle.jpg").unwrap();
+    }
+
+    #[test]
+    #[ignore = "visual check"]
+    fn logo_lissajous() {
+        let mut plotter = Plotter::new(50, 50, "tests/lincoln.jpeg", 100, 255).unwrap();
+        plotter.thickness = 1;
+        plotter.draw_lissajous(1, 1);
+        plotter.canvas.save("tests/lincoln_lissajous.jpg").unwrap();
+    }
+
+    #[test]
+    #[ignore = "visual check"]
+    fn logo_sawtooth() {
+        let mut plotter = Plotter::new(50, 50, "tests/lincoln.jpeg", 100, 255).unwrap();
+        plotter.draw_sawtooth(4);
+        plotter.canvas.save("tests/lincoln_sawtooth.jpg").unwrap();
+    }
+
+    #[test]
+    #[ignore = "visual check"]
+    fn logo_vertical() {
+        let mut plotter = Plotter::new(50, 50, "tests/lincoln.jpeg", 100, 255).unwrap();
+        plotter.draw_vertical(4);
+        plotter
+            .canvas
+            .save("tests/lincoln_sine_vertical.jpg")
+            .unwrap();
+    }
+
+    /// Horizontal gradient, dark on the left and white on the right.
+    fn gradient() -> DynamicImage {
+        DynamicImage::ImageLuma8(GrayImage::from_fn(400, 300, |x, _| {
+            image::Luma([(x * 255 / 399) as u8])
+        }))
+    }
+
+    /// Image whose left half is black and right half is white.
+    fn halves() -> DynamicImage {
+        DynamicImage::ImageLuma8(GrayImage::from_fn(400, 300, |x, _| {
+            image::Luma([if x < 200 { 0 } else { 255 }])
+        }))
+    }
+
+    /// Same as [`Plotter::draw`] with a single sine wave per cell, traced cell by cell.
+    fn trace_sines(plotter: &mut Plotter, thickness: u32) {
+        let (cw, qwave) = (plotter.cell_width(), plotter.quarter_wavelength());
+        let amax = plotter.effective_max_amplitude(thickness);
+        let (nw, nh) = plotter.source.dimensions();
+        for cell_y in 0..nh {
+            let y = plotter.cell_to_sine_start_y(cell_y) as i32;
+            for cell_x in 0..nw {
+                let a = plotter.amplitude(cell_x, cell_y, amax);
+                let sine = Sine::new(Point::new((cw * cell_x) as i32, y), a, qwave);
+                sine.draw_thick(&mut plotter.canvas, thickness);
+            }
+        }
+    }
+
+    /// Write `image` to a temporary file, for the tests going through a source path.
+    #[cfg(feature = "png")]
+    fn save_fixture(image: DynamicImage, name: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(name);
+        image.save(&path).unwrap();
+        path
+    }
+
+    #[test]
+    #[cfg(feature = "png")]
+    fn process_dir() {
+        let dir = std::env::temp_dir().join("sineart_batch");
+        let (input, output) = (dir.join("in"), dir.join("out"));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&input).unwrap();
+        gradient().save(input.join("a.png")).unwrap();
+        halves().save(input.join("b.png")).unwrap();
+        std::fs::write(input.join("notes.txt"), "not an image").unwrap();
+
+        let settings = PlotterBuilder::new().cells(8, 6).scale(50);
+        let report = super::process_dir(&input, &output, &settings).unwrap();
+        assert_eq!(report.rendered.len(), 2);
+        assert_eq!(report.skipped, [input.join("notes.txt")]);
+        assert!(report.failed.is_empty());
+        for name in ["a_sine.png", "b_sine.png"] {
+            assert!(image::open(output.join(name)).is_ok());
+        }
+
+        // a broken image is reported without stopping the batch
+        std::fs::write(input.join("c.png"), "not a png either").unwrap();
+        let report = super::process_dir(&input, &output, &settings).unwrap();
+        assert_eq!(report.rendered.len(), 2);
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].0, input.join("c.png"));
+    }
+
+    #[test]
+    #[ignore = "visual check"]
+    fn logo_underlay() {
+        let mut plotter = Plotter::new(50, 50, "tests/lincoln.jpeg", 100, 255).unwrap();
+        plotter.draw_with_underlay(4, 0.2);
+        plotter
+            .canvas
+            .save("tests/lincoln_sine_underlay.jpg")
+            .unwrap();
+    }
+
+    #[test]
+    fn underlay() {
+        let mut plain = Plotter::from_image(4, 3, halves(), 100, 255).unwrap();
+        plain.draw(2);
+        let mut underlaid = Plotter::from_image(4, 3, halves(), 100, 255).unwrap();
+        underlaid.draw_with_underlay(2, 0.0);
+        assert_eq!(plain.canvas, underlaid.canvas);
+
+        // the flat right half only has a horizontal line per row, the rest shows the underlay
+        underlaid.draw_with_underlay(2, 0.25);
+        let (iw, ih) = (underlaid.canvas.iw as i32, underlaid.canvas.ih as i32);
+        assert_eq!(underlaid.canvas.get_xy(iw - 1, ih - 1), 255);
+        assert_eq!(underlaid.canvas.get_xy(0, ih - 1), 191);
+    }
+
+    #[test]
+    fn draw_with_progress() {
+        let mut plotter = Plotter::from_image(4, 3, halves(), 100, 255).unwrap();
+        let mut calls = Vec::new();
+        plotter.draw_with_progress(2, |done, total| calls.push((done, total)));
+        assert_eq!(calls, vec![(1, 3), (2, 3), (3, 3)]);
+
+        let mut plain = Plotter::from_image(4, 3, halves(), 100, 255).unwrap();
+        plain.draw(2);
+        assert_eq!(plain.canvas, plotter.canvas);
+    }
+
+    #[test]
+    fn plan() {
+        let mut plotter = Plotter::from_image(10, 5, gradient(), 100, 255).unwrap();
+        let plan = plotter.plan();
+        let canvas = &plotter.canvas;
+        assert_eq!(
+            (plan.fw, plan.fh, plan.iw, plan.ih),
+            (canvas.fw, canvas.fh, canvas.iw, canvas.ih)
+        );
+        assert_eq!(plan.cells, 50);
+        assert_eq!(plan.cell_width, (canvas.iw - 1) / 10);
+        assert_eq!(plan.cell_height, canvas.ih / 5);
+        assert_eq!(plan.quarter_wavelength * 4, plan.cell_width);
+        assert_eq!(plan.max_amplitude, plotter.max_amplitude());
+
+        // planning leaves the canvas alone, and drawing does not change the plan
+        assert!(plotter.canvas.image().pixels().all(|p| p.0[0] == 255));
+        plotter.draw(2);
+        assert_eq!(plotter.plan(), plan);
+    }
+
+    #[test]
+    fn shape() {
+        let mut sine = Plotter::from_image(8, 6, gradient(), 100, 255).unwrap();
+        sine.draw(2);
+        let mut default = Plotter::from_image(8, 6, gradient(), 100, 255).unwrap();
+        default.set_shape(StrokeShape::default());
+        default.draw(2);
+        assert_eq!(sine.canvas, default.canvas);
+
+        let mut triangle = Plotter::from_image(8, 6, gradient(), 100, 255).unwrap();
+        triangle.set_shape(StrokeShape::Triangle);
+        let stats = triangle.draw_with_stats(2);
+        assert_ne!(sine.canvas, triangle.canvas);
+        assert_eq!(stats.waves, 48);
+
+        // same as drawing the waves directly
+        let mut direct = Plotter::from_image(8, 6, gradient(), 100, 255).unwrap();
+        direct.draw_triangle(2);
+        assert_eq!(triangle.canvas, direct.canvas);
+    }
+
+    #[test]
+    fn variable_thickness() {
+        let source = GrayImage::from_fn(400, 300, |x, _| {
+            image::Luma([if x < 200 { 0 } else { 128 }])
+        });
+        let source = DynamicImage::ImageLuma8(source);
+        let mut plotter = Plotter::from_image(4, 3, source, 100, 255).unwrap();
+        let black = plotter.cell_thickness(0, 0, 6);
+        let gray = plotter.cell_thickness(3, 0, 6);
+        assert_eq!(black, 6);
+        assert!(gray > 1 && gray < black);
+
+        // the thickest strokes spread further across the center line than the thin ones
+        plotter.draw_variable_thickness(6);
+        let cw = plotter.cell_width() as i32;
+        let y = plotter.cell_to_sine_start_y(0) as i32;
+        let inked = |x: i32| plotter.canvas.get_xy(x, y) == 0;
+        let width = |from: i32| (from..from + cw).filter(|&x| inked(x)).count();
+        assert!(width(0) > width(3 * cw));
+    }
+
+    #[test]
+    #[ignore = "visual check"]
+    fn colored() {
+        let source = RgbImage::from_fn(400, 300, |x, y| {
+            Rgb([(x * 255 / 399) as u8, (y * 255 / 299) as u8, 128])
+        });
+        let source = DynamicImage::ImageRgb8(source);
+        let plotter = Plotter::from_image(40, 30, source, 200, 255).unwrap();
+        plotter.draw_colored(2).save("tests/colored.png").unwrap();
+    }
+
+    #[test]
+    fn draw_colored() {
+        let source = RgbImage::from_fn(400, 300, |x, _| {
+            Rgb(if x < 200 {
+                [255, 0, 0]
+            } else {
+                [255, 255, 255]
+            })
+        });
+        let source = DynamicImage::ImageRgb8(source);
+        let plotter = Plotter::from_image(4, 3, source, 100, 255).unwrap();
+        let canvas = plotter.draw_colored(2);
+
+        let (cw, ch) = (plotter.cell_width() as i32, plotter.cell_height() as i32);
+        let mut inked = 0;
+        for x in 0..cw {
+            for y in 0..ch {
+                let Rgb([r, g, b]) = canvas.get_xy(x, y);
+                if [r, g, b] != [255; 3] {
+                    inked += 1;
+                    assert!(r > 200 && g < 50 && b < 50, "{:?}", (r, g, b));
+                }
+            }
+        }
+        assert!(inked > 0);
+    }
+
+    #[test]
+    fn duotone() {
+        let mut plotter = Plotter::from_image(4, 3, halves(), 100, 255).unwrap();
+        plotter.thickness = 2;
+        let (shadow, highlight) = (Rgb([20, 30, 90]), Rgb([250, 220, 150]));
+        let canvas = plotter.draw_duotone(shadow, highlight);
+
+        let (iw, ih) = (canvas.iw as i32, canvas.ih as i32);
+        let pixels: Vec<_> = (0..iw)
+            .flat_map(|x| (0..ih).map(move |y| (x, y)))
+            .map(|(x, y)| canvas.get_xy(x, y).0)
+            .collect();
+        let near_shadow = |p: &[u8; 3]| p.iter().zip(shadow.0).all(|(&p, s)| p.abs_diff(s) <= 10);
+        assert!(pixels.iter().any(near_shadow));
+        // antialiased edges blend between the two colors
+        let between = |p: &[u8; 3]| {
+            (0..3).all(|c| {
+                let (s, h) = (shadow.0[c], highlight.0[c]);
+                min(s, h) <= p[c] && p[c] <= max(s, h)
+            })
+        };
+        assert!(pixels.iter().all(between));
+        assert!(pixels.iter().any(|p| p[0] > 20 && p[0] < 250));
+
+        // the space between rows is left alone
+        let y = (plotter.cell_to_sine_start_y(0) + plotter.cell_height() / 2) as i32;
+        assert_eq!(canvas.get_xy(3 * plotter.cell_width() as i32, y), highlight);
+        assert_eq!(canvas.get_xy(0, 0), highlight);
+    }
+
+    #[test]
+    fn subwaves() {
+        // 104% scale for an even quarter wavelength of 26 pixels
+        let mut plotter = Plotter::from_image(4, 3, halves(), 104, 255).unwrap();
+        plotter.set_subwaves(2);
+        plotter.draw(0);
+        let (cw, qwave) = (
+            plotter.cell_width() as i32,
+            plotter.quarter_wavelength() as i32,
+        );
+        assert_eq!(qwave % 2, 0);
+        let q = qwave / 2;
+        let y = plotter.cell_to_sine_start_y(0) as i32;
+        let a = plotter.amplitude(0, 0, plotter.effective_max_amplitude(0)) as i32;
+        let inked = |canvas: &Canvas, x: i32, y: i32| canvas.get_xy(x, y) == 0;
+
+        // two crests and two troughs per cell, ending on the cell border
+        for crest in [q, 5 * q] {
+            assert!(inked(&plotter.canvas, crest, y + a));
+        }
+        for trough in [3 * q, 7 * q] {
+            assert!(inked(&plotter.canvas, trough, y - a));
+        }
+        assert!(!inked(&plotter.canvas, qwave, y + a));
+        assert!(inked(&plotter.canvas, cw, y));
+
+        // any remainder is flat
+        plotter.canvas.clear();
+        plotter.set_subwaves(3);
+        plotter.draw(0);
+        let end = 4 * 3 * (qwave / 3);
+        assert!((end..=cw).all(|x| inked(&plotter.canvas, x, y)));
+    }
+
+    #[test]
+    fn wavelength_px() {
+        let mut plotter = Plotter::from_image(4, 3, halves(), 100, 255).unwrap();
+        let y = plotter.cell_to_sine_start_y(0) as i32;
+        let a = plotter.amplitude(0, 0, plotter.effective_max_amplitude(0)) as i32;
+        for wavelength in [12, 24, 30, 41] {
+            plotter.canvas.clear();
+            plotter.set_wavelength_px(Some(wavelength));
+            plotter.draw(0);
+            // start of every run of crest pixels in the first cell
+            let crests = (0..plotter.cell_width() as i32)
+                .filter(|&x| {
+                    plotter.canvas.get_xy(x, y + a) == 0
+                        && (x == 0 || plotter.canvas.get_xy(x - 1, y + a) != 0)
+                })
+                .collect::<Vec<_>>();
+            assert!(crests.len() > 1, "{:?} for {} pixels", crests, wavelength);
+            for pair in crests.windows(2) {
+                let period = (pair[1] - pair[0]) as u32;
+                // waves are whole quarters, so within a pixel when the wavelength allows it
+                let tolerance = if wavelength % 4 == 0 { 1 } else { 2 };
+                assert!(
+                    period.abs_diff(wavelength) <= tolerance,
+                    "{} for {}",
+                    period,
+                    wavelength
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn connect_rows() {
+        let joined = |plotter: &Plotter, x: i32, rows: (u32, u32)| {
+            let (top, bottom) = (
+                plotter.cell_to_sine_start_y(rows.0) as i32,
+                plotter.cell_to_sine_start_y(rows.1) as i32,
+            );
+            (bottom..=top).all(|y| plotter.canvas.get_xy(x, y) == 0)
+        };
+        let mut plotter = Plotter::from_image(4, 3, halves(), 100, 255).unwrap();
+        plotter.draw(1);
+        let right = plotter.canvas.iw as i32 - 1;
+        assert!(!joined(&plotter, right, (0, 1)));
+
+        plotter.canvas.clear();
+        plotter.set_connect_rows(true);
+        plotter.draw(1);
+        assert!(joined(&plotter, right, (0, 1)));
+        assert!(joined(&plotter, 0, (1, 2)));
+        assert!(!joined(&plotter, 0, (0, 1)));
+        assert!(!joined(&plotter, right, (1, 2)));
+    }
+
+    #[test]
+    fn stagger() {
+        let crest = |stagger: bool, cell_y: u32| {
+            let mut plotter = Plotter::from_image(4, 3, halves(), 100, 255).unwrap();
+            plotter.set_stagger(stagger);
+            plotter.draw(0);
+            let y = plotter.cell_to_sine_start_y(cell_y) as i32;
+            let a = plotter.amplitude(0, cell_y, plotter.effective_max_amplitude(0)) as i32;
+            (0..plotter.cell_width() as i32)
+                .find(|&x| plotter.canvas.get_xy(x, y + a) == 0)
+                .unwrap()
+        };
+
+        // even rows are untouched, odd rows peak half a period later
+        assert_eq!(crest(true, 0), crest(false, 0));
+        assert_eq!(crest(true, 2), crest(false, 2));
+        assert_ne!(crest(true, 1), crest(false, 1));
+    }
+
+    #[test]
+    fn settings_apply_to_every_mode() {
+        let modes: [fn(&mut Plotter); 10] = [
+            |plotter| plotter.draw(1),
+            |plotter| plotter.draw_antialiased(1),
+            |plotter| plotter.draw_variable_thickness(3),
+            |plotter| plotter.draw_fm(1),
+            |plotter| plotter.draw_vertical(1),
+            |plotter| plotter.draw_triangle(1),
+            |plotter| plotter.draw_lissajous(1, 1),
+            |plotter| plotter.draw_supersampled(1, 2),
+            |plotter| {
+                let size = &plotter.canvas;
+                let mut canvas = Canvas::new([size.fh, size.fw], [size.ih, size.iw]);
+                plotter.draw_onto(&mut canvas, 1, 0);
+                plotter.canvas = canvas;
+            },
+            |plotter| {
+                plotter.draw_adaptive(0.0);
+            },
+        ];
+        let settings: [fn(&mut Plotter); 4] = [
+            |plotter| plotter.set_subwaves(3),
+            |plotter| plotter.set_stagger(true),
+            |plotter| plotter.set_jitter(2.0, 1),
+            |plotter| plotter.set_connect_rows(true),
+        ];
+        for (i, mode) in modes.iter().enumerate() {
+            for (j, setting) in settings.iter().enumerate() {
+                // FM sets its own wavelengths, antialiased strokes are not jittered, and only sine
+                // waves are staggered
+                let exempt = matches!((i, j), (3, 0) | (1, 2) | (5, 1) | (6, 1));
+                let mut plain = Plotter::from_image(4, 3, gradient(), 50, 255).unwrap();
+                mode(&mut plain);
+                let mut configured = Plotter::from_image(4, 3, gradient(), 50, 255).unwrap();
+                setting(&mut configured);
+                mode(&mut configured);
+                let same = plain.canvas == configured.canvas;
+                assert_eq!(same, exempt, "mode {} with setting {}", i, j);
+            }
+        }
+
+        // color drawings too
+        let mut plotter = Plotter::from_image(4, 3, gradient(), 100, 255).unwrap();
+        let plain = plotter.draw_colored(1);
+        plotter.set_stagger(true);
+        let staggered = plotter.draw_colored(1);
+        let (iw, ih) = (plain.iw as i32, plain.ih as i32);
+        assert!((0..iw)
+            .flat_map(|x| (0..ih).map(move |y| (x, y)))
+            .any(|(x, y)| plain.get_xy(x, y) != staggered.get_xy(x, y)));
+
+        // and vector exports, without jitter
+        let mut plotter = Plotter::from_image(4, 3, gradient(), 100, 255).unwrap();
+        let (svg, path) = (plotter.to_svg(1.0), plotter.path_serpentine());
+        plotter.set_jitter(2.0, 1);
+        assert_eq!(plotter.to_svg(1.0), svg);
+        assert_eq!(plotter.path_serpentine(), path);
+        plotter.set_subwaves(3);
+        let segments = |svg: &str| svg.matches(" C ").count();
+        assert_eq!(segments(&plotter.to_svg(1.0)), 3 * segments(&svg));
+        assert!(plotter.path_serpentine().len() > path.len());
+        plotter.set_connect_rows(true);
+        let paths = |svg: &str| svg.matches("<path").count();
+        assert_eq!(paths(&plotter.to_svg(1.0)), 3 + 2);
+    }
+
+    #[test]
+    fn draw_antialiased() {
+        let gray = |canvas: &Canvas| {
+            canvas
+                .image()
+                .pixels()
+                .filter(|p| p.0[0] > 0 && p.0[0] < 255)
+                .count()
+        };
+        let mut aliased = Plotter::from_image(4, 3, halves(), 100, 255).unwrap();
+        aliased.draw(2);
+        assert_eq!(gray(&aliased.canvas), 0);
+
+        let mut antialiased = Plotter::from_image(4, 3, halves(), 100, 255).unwrap();
+        antialiased.draw_antialiased(2);
+        assert!(gray(&antialiased.canvas) > 0);
+
+        // only the outermost pixels are feathered, the core of the stroke is still solid
+        let (solid, soft) = (aliased.canvas.image(), antialiased.canvas.image());
+        for (x, y, pixel) in solid.enumerate_pixels() {
+            let core = (x.saturating_sub(1)..=min(x + 1, solid.width() - 1))
+                .all(|x| solid.get_pixel(x, y).0[0] == 0);
+            if core {
+                assert_eq!(soft.get_pixel(x, y).0[0], pixel.0[0], "({}, {})", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn cells_tile_canvas() {
+        let source = DynamicImage::ImageLuma8(GrayImage::new(40, 30));
+        for nw in 1..=40 {
+            for scale in [50, 99, 100, 101, 250, 333, 1000] {
+                let plotter = match Plotter::from_image(nw, 5, source.clone(), scale, 255) {
+                    Ok(plotter) => plotter,
+                    Err(_) => continue,
+                };
+                let iw = plotter.canvas.iw;
+                assert_eq!((iw - 1) % nw, 0, "{} cells at {}%", nw, scale);
+                assert_eq!(plotter.cell_width() % 4, 0, "{} cells at {}%", nw, scale);
+                assert_eq!(plotter.cell_width(), 4 * plotter.quarter_wavelength());
+                // as narrow as possible without shrinking the source
+                assert!(iw > 40 * scale / 100);
+                assert!(iw - 4 * nw <= 40 * scale / 100);
+            }
+        }
+    }
+
+    #[test]
+    fn geometry() {
+        let plotter = Plotter::from_image(10, 7, gradient(), 77, 255).unwrap();
+        let (nw, nh) = plotter.source_grid().dimensions();
+        let canvas = &plotter.canvas;
+        assert!(plotter.cell_width() * nw <= canvas.iw);
+        assert!(plotter.cell_height() * nh <= canvas.ih);
+        assert_eq!(plotter.quarter_wavelength() * 4, plotter.cell_width());
+        assert!(2 * plotter.max_amplitude() <= plotter.cell_height());
+
+        // center lines are half a cell apart from the edges, top row first
+        let top = plotter.cell_to_sine_start_y(0);
+        let bottom = plotter.cell_to_sine_start_y(nh - 1);
+        assert!(top > bottom);
+        assert!(bottom.abs_diff(plotter.cell_height() / 2) <= 1);
+        assert!(top < canvas.ih);
+    }
+
+    #[test]
+    fn draw_onto() {
+        let left = Plotter::from_image(4, 3, halves(), 50, 255).unwrap();
+        let right = Plotter::from_image(4, 3, gradient(), 50, 255).unwrap();
+        let (iw, ih) = (left.canvas.iw, left.canvas.ih);
+        let mut canvas = Canvas::with_background([ih, 2 * iw], [ih, 2 * iw], 255);
+        left.draw_onto(&mut canvas, 1, 0);
+        let offset = Point::new(iw as i32, 0);
+        right.draw_onto(&mut Translated(&mut canvas, offset), 1, 100);
+
+        let values = |xs: std::ops::Range<u32>| {
+            let mut values = xs
+                .flat_map(|x| (0..ih).map(move |y| (x, y)))
+                .map(|(x, y)| canvas.get_xy(x as i32, y as i32))
+                .collect::<Vec<_>>();
+            values.sort();
+            values.dedup();
+            values
+        };
+        assert_eq!(values(0..iw - 1), [0, 255]);
+        assert_eq!(values(iw + 1..2 * iw), [100, 255]);
+    }
+
+    #[test]
+    fn draw_channel_separation() {
+        let source = DynamicImage::ImageRgb8(RgbImage::from_pixel(400, 300, Rgb([0, 0, 0])));
+        let mut plotter = Plotter::from_image(4, 3, source, 100, 255).unwrap();
+        plotter.thickness = 1;
+        let mut reference = Canvas::new(
+            [plotter.canvas.fh, plotter.canvas.fw],
+            [plotter.canvas.ih, plotter.canvas.iw],
+        );
+        plotter.draw_onto(&mut reference, 1, 0);
+        let canvas = plotter.draw_channel_separation(5);
+
+        // pixels of a single layer's color are inked on the reference, moved by that layer, unless
+        // they were moved in from outside of it
+        for (channel, shift) in [(0, -5), (1, 0), (2, 5)] {
+            let mut tint = [0; 3];
+            tint[channel] = 255;
+            let mut found = 0;
+            for x in 0..plotter.canvas.iw as i32 {
+                for y in 0..plotter.canvas.ih as i32 {
+                    if canvas.try_get_xy(x, y) == Some(Rgb(tint)) {
+                        assert_ne!(reference.try_get_xy(x - shift, y + shift), Some(255));
+                        found += 1;
+                    }
+                }
+            }
+            assert!(found > 0, "no pixels for channel {}", channel);
+        }
+    }
+
+    #[test]
+    fn value_noise() {
+        let samples = (0..200)
+            .map(|k| super::value_noise(3, k as f64 * 0.13, k as f64 * 0.07))
+            .collect::<Vec<_>>();
+        assert!(samples.iter().all(|n| (-1.0..=1.0).contains(n)));
+        assert!(samples
+            .windows(2)
+            .all(|pair| (pair[1] - pair[0]).abs() < 0.5));
+        assert_eq!(
+            super::value_noise(3, 1.5, 2.5),
+            super::value_noise(3, 1.5, 2.5)
+        );
+        assert_ne!(
+            super::value_noise(3, 1.5, 2.5),
+            super::value_noise(4, 1.5, 2.5)
+        );
+    }
+
+    #[test]
+    fn noise() {
+        let amplitudes = |strength: f64| {
+            let mut plotter = Plotter::from_image(10, 5, gradient(), 100, 255).unwrap();
+            plotter.set_noise(strength, 3.0);
+            let amax = plotter.effective_max_amplitude(1);
+            let amplitudes = (0..5)
+                .flat_map(|y| (0..10).map(move |x| (x, y)))
+                .map(|(x, y)| plotter.amplitude(x, y, amax))
+                .collect::<Vec<_>>();
+            assert!(amplitudes.iter().all(|&a| a <= amax));
+            plotter.draw(1);
+            (amplitudes, plotter.canvas)
+        };
+        let plain = {
+            let mut plotter = Plotter::from_image(10, 5, gradient(), 100, 255).unwrap();
+            plotter.draw(1);
+            plotter.canvas
+        };
+        assert_eq!(amplitudes(0.0).1, plain);
+        let (still, _) = amplitudes(0.0);
+        let (noisy, canvas) = amplitudes(0.5);
+        assert!(still.iter().zip(noisy.iter()).any(|(a, b)| a != b));
+        assert_ne!(canvas, plain);
+    }
+
+    #[test]
+    fn draw_adaptive() {
+        // noisy top left quarter, flat gray elsewhere
+        let source = DynamicImage::ImageLuma8(GrayImage::from_fn(400, 300, |x, y| {
+            let busy = x < 200 && y < 150 && (x / 3 + y / 3) % 2 == 0;
+            image::Luma([if busy { 0 } else { 128 }])
+        }));
+        let mut plotter = Plotter::from_image(4, 4, source.clone(), 100, 255).unwrap();
+        for y in 0..4 {
+            for x in 0..4 {
+                assert_eq!(
+                    plotter.is_detailed(x, y, 0.01),
+                    x < 2 && y < 2,
+                    "{} {}",
+                    x,
+                    y
+                );
+            }
+        }
+
+        let stats = plotter.draw_adaptive(0.01);
+        assert_eq!(stats.waves, 12 + 4 * 4);
+        assert!(stats.max_amplitude <= plotter.effective_max_amplitude(plotter.thickness));
+
+        // split cells have their waves above and below the center line, plain ones on it
+        let (cw, ch) = (plotter.cell_width() as i32, plotter.cell_height() as i32);
+        let inked = |x: i32, y: i32| plotter.canvas.get_xy(x, y) == 0;
+        let y = plotter.cell_to_sine_start_y(0) as i32;
+        assert!(inked(cw / 2, y + ch / 4) && inked(cw / 2, y - ch / 4));
+        assert!(!inked(cw / 2, y));
+        assert!(inked(3 * cw + cw / 2, y));
+
+        // a threshold above any variance gives the plain render
+        let mut plain = Plotter::from_image(4, 4, source.clone(), 100, 255).unwrap();
+        plain.render();
+        let mut adaptive = Plotter::from_image(4, 4, source, 100, 255).unwrap();
+        assert_eq!(adaptive.draw_adaptive(1.0).waves, 16);
+        assert_eq!(adaptive.canvas, plain.canvas);
+    }
+
+    #[test]
+    fn preview() {
+        let mut plotter = Plotter::from_image(10, 8, gradient(), 100, 255).unwrap();
+        plotter.thickness = 3;
+        for max_dim in [100, 211, 300] {
+            let preview = plotter.preview(max_dim);
+            let size = max(preview.width(), preview.height());
+            assert!(size <= max_dim, "{} for {}", size, max_dim);
+            assert!(preview.width() < plotter.canvas.fw);
+            assert!(preview.pixels().any(|p| p.0[0] == 0));
+        }
+
+        // nothing to scale down when the render is small enough already
+        plotter.render();
+        let max_dim = max(plotter.canvas.fw, plotter.canvas.fh);
+        assert_eq!(&plotter.preview(max_dim), plotter.canvas.image());
+    }
+
+    #[test]
+    fn fidelity() {
+        let mut waves = Plotter::from_image(10, 8, halves(), 100, 255).unwrap();
+        waves.draw(2);
+        let mut flat = Plotter::from_image(10, 8, halves(), 100, 255).unwrap();
+        flat.set_amplitude_ratio(0.0);
+        flat.draw(2);
+        let (waves, flat) = (waves.fidelity(), flat.fidelity());
+        assert!(waves.psnr > flat.psnr, "{:?} against {:?}", waves, flat);
+        assert!(waves.ssim > flat.ssim, "{:?} against {:?}", waves, flat);
+        assert!(waves.ssim <= 1.0);
+
+        assert_eq!(structural_similarity(&[(10.0, 10.0), (200.0, 200.0)]), 1.0);
+        assert!(structural_similarity(&[(10.0, 200.0), (200.0, 10.0)]) < 0.0);
+    }
+
+    #[test]
+    fn mappings() {
+        let mappings = [
+            Mapping::Linear,
+            Mapping::Gamma(0.5),
+            Mapping::Gamma(2.2),
+            Mapping::Log,
+            Mapping::Srgb,
+        ];
+        for mapping in mappings {
+            let factors = (0..=255).map(|p| mapping.factor(p)).collect::<Vec<_>>();
+            assert!(
+                factors.windows(2).all(|pair| pair[0] <= pair[1]),
+                "{:?}",
+                mapping
+            );
+            assert_eq!(factors[0], 0.0, "{:?}", mapping);
+            assert!((factors[255] - 1.0).abs() < 1e-9, "{:?}", mapping);
+        }
+        for pixel in 1..64 {
+            assert!(Mapping::Log.factor(pixel) > Mapping::Linear.factor(pixel));
+        }
+        assert!(Mapping::Srgb.factor(128) < Mapping::Linear.factor(128));
+
+        // log mapped shadows get smaller waves
+        let mut plotter = Plotter::from_image(10, 5, gradient(), 100, 255).unwrap();
+        let linear = plotter.amplitude(2, 0, 100);
+        plotter.set_mapping(Mapping::Log);
+        assert!(plotter.amplitude(2, 0, 100) < linear);
+    }
+
+    #[test]
+    fn draw_with_grid() {
+        let mut plotter = Plotter::from_image(4, 3, halves(), 100, 255).unwrap();
+        plotter.draw_with_grid(1, 128);
+        let cw = plotter.cell_width() as i32;
+        for x in 0..4 {
+            assert_eq!(plotter.canvas.get_xy(x * cw, 1), 128);
+        }
+        assert_eq!(plotter.canvas.get_xy(4 * cw, 1), 128);
+        assert_eq!(
+            plotter.canvas.get_xy(cw / 2, plotter.cell_height() as i32),
+            128
+        );
+
+        // the waves are otherwise untouched
+        let mut plain = Plotter::from_image(4, 3, halves(), 100, 255).unwrap();
+        plain.draw(1);
+        let changed = plain
+            .canvas
+            .image()
+            .pixels()
+            .zip(plotter.canvas.image().pixels())
+            .filter(|(a, b)| a != b);
+        assert!(changed.clone().count() > 0);
+        assert!(changed.into_iter().all(|(_, b)| b.0[0] == 128));
+    }
+
+    #[test]
+    fn draw_lissajous() {
+        let mut plotter = Plotter::from_image(4, 3, halves(), 100, 255).unwrap();
+        plotter.thickness = 0;
+        plotter.draw_lissajous(1, 1);
+        let (cw, qwave) = (
+            plotter.cell_width() as i32,
+            plotter.quarter_wavelength() as i32,
+        );
+        let y = plotter.cell_to_sine_start_y(0) as i32;
+        let inked = |x: i32, y: i32| plotter.canvas.get_xy(x, y) == 0;
+
+        // black cells are open ellipses, white ones closed into a diagonal through their center
+        assert!(!inked(2 * qwave, y));
+        assert!(inked(2 * qwave, y + plotter.max_amplitude() as i32));
+        assert!(inked(3 * cw + 2 * qwave, y));
+    }
+
+    #[test]
+    fn jitter() {
+        let render = |jitter: f64, seed: u64| {
+            let mut plotter = Plotter::from_image(4, 3, halves(), 100, 255).unwrap();
+            plotter.set_jitter(jitter, seed);
+            plotter.draw(1);
+            plotter.canvas
+        };
+        assert_eq!(render(3.0, 7), render(3.0, 7));
+        assert_ne!(render(3.0, 7), render(3.0, 8));
+        assert_ne!(render(3.0, 7), render(0.0, 7));
+        assert_eq!(render(0.0, 7), render(0.0, 8));
+    }
+
+    #[test]
+    fn draw_with_stats() {
+        let mut plotter = Plotter::from_image(4, 3, halves(), 100, 255).unwrap();
+        let stats = plotter.draw_with_stats(2);
+        assert_eq!(stats.waves, 4 * 3);
+        assert!(stats.pixels_drawn > 0);
+        assert_eq!(stats.min_amplitude, 0);
+        assert_eq!(stats.max_amplitude, plotter.effective_max_amplitude(2));
+
+        // every written pixel is counted, so at least all the ink ends up in there
+        let inked = plotter.canvas.image().pixels().filter(|p| p.0[0] == 0);
+        assert!(stats.pixels_drawn >= inked.count() as u64);
+    }
+
+    #[test]
+    fn render_tiled() {
+        let configured = || {
+            let mut plotter = Plotter::from_image(4, 5, halves(), 100, 255).unwrap();
+            plotter.set_jitter(2.0, 3);
+            plotter.set_connect_rows(true);
+            plotter
+        };
+        let plotter = configured();
+        let mut full = configured();
+        full.render();
+        for tile_rows in [1, 2, 5, 8] {
+            let (fw, fh) = plotter.canvas.image().dimensions();
+            let mut tiled = GrayImage::new(fw, fh);
+            let mut next = 0;
+            plotter.render_tiled(tile_rows, |top, tile| {
+                assert_eq!(top, next);
+                assert_eq!(tile.width(), fw);
+                next += tile.height();
+                imageops::replace(&mut tiled, tile, 0, top as i64);
+            });
+            assert_eq!(next, fh);
+
+            // the canvas is left blank, and a full render matches the tiles pixel for pixel
+            assert!(plotter.canvas.image().pixels().all(|p| p.0[0] == 255));
+            assert_eq!(&tiled, full.canvas.image(), "{} rows per tile", tile_rows);
+        }
+    }
+
+    #[test]
+    fn invert() {
+        let mut plotter = Plotter::from_image(4, 3, halves(), 100, 255).unwrap();
+        let amax = plotter.max_amplitude();
+        assert_eq!(plotter.amplitude(0, 0, amax), amax);
+        assert_eq!(plotter.amplitude(3, 0, amax), 0);
+
+        plotter.set_invert(true);
+        assert_eq!(plotter.amplitude(0, 0, amax), 0);
+        assert_eq!(plotter.amplitude(3, 0, amax), amax);
+    }
+
+    #[test]
+    fn amplitude_map() {
+        let plotter = Plotter::from_image(10, 5, gradient(), 100, 255)
+            .unwrap()
+            .with_amplitude_map(|pixel: u8, max: u32| if pixel < 128 { max } else { 0 });
+        let amax = plotter.max_amplitude();
+        let mut amplitudes: Vec<_> = (0..10).map(|x| plotter.amplitude(x, 0, amax)).collect();
+        amplitudes.sort();
+        amplitudes.dedup();
+        assert_eq!(amplitudes, vec![0, amax]);
+
+        let plotter = plotter.with_amplitude_map(|_: u8, max: u32| 2 * max);
+        assert_eq!(plotter.amplitude(0, 0, amax), amax);
+
+        // plotters with custom maps can still be handed to other threads
+        let plotter = std::thread::spawn(move || plotter).join().unwrap();
+        assert_eq!(plotter.amplitude(0, 0, amax), amax);
+        fn shared<T: Send + Sync>(_: &T) {}
+        shared(&plotter);
+    }
+
+    #[test]
+    fn render_frames() {
+        let mut plotter = Plotter::from_image(8, 6, gradient(), 100, 255).unwrap();
+        let frames = plotter.render_frames(5);
+        assert_eq!(frames.len(), 5);
+        assert_ne!(frames[0], frames[4]);
+
+        plotter.render();
+        assert_eq!(&frames[4], plotter.canvas.image());
+        assert!(plotter.render_frames(0).is_empty());
+
+        // in any shape
+        plotter.set_shape(StrokeShape::Triangle);
+        let frames = plotter.render_frames(3);
+        plotter.canvas.clear();
+        plotter.render();
+        assert_eq!(&frames[2], plotter.canvas.image());
+    }
+
+    #[test]
+    #[cfg(feature = "gif")]
+    #[ignore = "visual check"]
+    fn logo_gif() {
+        let plotter = Plotter::new(50, 50, "tests/lincoln.jpeg", 100, 255).unwrap();
+        save_gif(&plotter.render_frames(10), "tests/lincoln_sine.gif", 100).unwrap();
+    }
+
+    #[test]
+    fn amplitude_ratio() {
+        let mut plotter = Plotter::from_image(10, 5, gradient(), 100, 255).unwrap();
+        plotter.set_amplitude_ratio(1.0);
+        let full = plotter.max_amplitude();
+        plotter.set_amplitude_ratio(0.5);
+        assert_eq!(plotter.max_amplitude(), full / 2);
+
+        plotter.set_amplitude_ratio(2.0);
+        assert_eq!(plotter.max_amplitude(), full);
+        plotter.set_amplitude_ratio(-1.0);
+        assert_eq!(plotter.max_amplitude(), 0);
+    }
+
+    #[test]
+    fn supersampled() {
+        let mut plain = Plotter::from_image(8, 6, gradient(), 100, 255).unwrap();
+        plain.draw(1);
+        let mut supersampled = Plotter::from_image(8, 6, gradient(), 100, 255).unwrap();
+        supersampled.draw_supersampled(1, 1);
+        assert_eq!(supersampled.canvas, plain.canvas);
+
+        supersampled.draw_supersampled(1, 3);
+        let image = supersampled.canvas.image();
+        assert!(image.pixels().any(|p| p.0[0] > 0 && p.0[0] < 255));
+
+        // in any shape
+        plain.canvas.clear();
+        plain.set_shape(StrokeShape::Square);
+        plain.draw(1);
+        supersampled.canvas.clear();
+        supersampled.set_shape(StrokeShape::Square);
+        supersampled.draw_supersampled(1, 1);
+        assert_eq!(supersampled.canvas, plain.canvas);
+    }
+
+    #[test]
+    #[ignore = "visual check"]
+    fn logo_supersampled() {
+        let mut plotter = Plotter::new(50, 50, "tests/lincoln.jpeg", 100, 255).unwrap();
+        plotter.draw_supersampled(2, 3);
+        plotter
+            .canvas
+            .save("tests/lincoln_sine_supersampled.jpg")
+            .unwrap();
+    }
+
+    #[test]
+    fn templates() {
+        let mut templated = Plotter::from_image(16, 12, gradient(), 100, 255).unwrap();
+        templated.draw(2);
+        let mut naive = Plotter::from_image(16, 12, gradient(), 100, 255).unwrap();
+        trace_sines(&mut naive, 2);
+        assert_eq!(templated.canvas, naive.canvas);
+    }
+
+    #[test]
+    fn continuous() {
+        let mut plotter = Plotter::from_image(4, 3, halves(), 100, 255).unwrap();
+        let cw = plotter.cell_width() as f64;
+        let amax = plotter.max_amplitude();
+        let a: Vec<_> = (0..4)
+            .map(|x| plotter.amplitude(x, 0, amax) as f64)
+            .collect();
+        assert_eq!(plotter.continuous_amplitude(0.0, 0, amax), a[0]);
+        assert_eq!(plotter.continuous_amplitude(1.5 * cw, 0, amax), a[1]);
+        assert_eq!(
+            plotter.continuous_amplitude(2.0 * cw, 0, amax),
+            (a[1] + a[2]) / 2.0
+        );
+        assert_eq!(plotter.continuous_amplitude(4.0 * cw, 0, amax), a[3]);
+
+        // every column is drawn on every row, with no vertical gaps in between
+        plotter.draw_continuous(0);
+        let y0 = plotter.cell_to_sine_start_y(1) as i32;
+        let band = y0 - amax as i32..=y0 + amax as i32;
+        let rows = |x: i32| -> Vec<i32> {
+            band.clone()
+                .filter(|&y| plotter.canvas.get_xy(x, y) == 0)
+                .collect()
+        };
+        for x in 0..(4.0 * cw) as i32 {
+            let (here, next) = (rows(x), rows(x + 1));
+            assert!(!here.is_empty());
+            assert!(here.windows(2).all(|pair| pair[1] == pair[0] + 1));
+            assert!(here.iter().any(|y| next.iter().any(|n| (y - n).abs() <= 1)));
+        }
+    }
+
+    #[test]
+    #[ignore = "visual check"]
+    fn logo_continuous() {
+        let mut plotter = Plotter::new(50, 50, "tests/lincoln.jpeg", 100, 255).unwrap();
+        plotter.draw_continuous(2);
+        plotter
+            .canvas
+            .save("tests/lincoln_sine_continuous.jpg")
+            .unwrap();
+    }
+
+    #[test]
+    fn effective_max_amplitude() {
+        let plotter = Plotter::from_image(10, 5, gradient(), 100, 255).unwrap();
+        let amax = plotter.max_amplitude();
+        assert_eq!(plotter.effective_max_amplitude(0), amax);
+        assert!(plotter.effective_max_amplitude(4) < plotter.effective_max_amplitude(2));
+        assert!(plotter.effective_max_amplitude(2) < amax);
+        assert_eq!(plotter.effective_max_amplitude(amax + 1), 0);
+    }
+
+    #[test]
+    fn amplitude_floor() {
+        let mut plotter = Plotter::from_image(4, 3, halves(), 100, 255).unwrap();
+        let amax = plotter.max_amplitude();
+        assert_eq!(plotter.amplitude(3, 0, amax), 0);
+
+        plotter.set_amplitude_floor(0.25);
+        let floor = (amax as f64 * 0.25) as u32;
+        assert!(floor > 0);
+        assert_eq!(plotter.amplitude(3, 0, amax), floor);
+        assert_eq!(plotter.amplitude(0, 0, amax), amax);
+
+        plotter.set_amplitude_floor(3.0);
+        assert_eq!(plotter.amplitude(3, 0, amax), amax);
+    }
+
+    #[test]
+    fn flat_cells() {
+        let white = DynamicImage::ImageLuma8(GrayImage::from_pixel(400, 300, image::Luma([255])));
+        let blank = || Plotter::from_image(8, 6, white.clone(), 100, 255).unwrap();
+        let mut plotter = blank();
+        plotter.draw(0);
+
+        let (cw, qwave) = (plotter.cell_width(), plotter.quarter_wavelength());
+        let rows: Vec<_> = (0..6).map(|y| plotter.cell_to_sine_start_y(y)).collect();
+        for y in 0..plotter.canvas.ih {
+            for x in 0..plotter.canvas.iw {
+                let cell_x = min(x / cw, 7);
+                let expected = rows.contains(&y) && x - cell_x * cw <= 4 * qwave;
+                let drawn = plotter.canvas.get_xy(x as i32, y as i32) == 0;
+                assert_eq!(drawn, expected, "({}, {})", x, y);
+            }
+        }
+
+        // same pixels as the traced waves, thick or not
+        for thickness in [0, 3] {
+            let mut traced = blank();
+            trace_sines(&mut traced, thickness);
+            let mut flat = blank();
+            flat.draw(thickness);
+            assert_eq!(flat.canvas, traced.canvas);
+        }
+    }
+
+    #[test]
+    fn gamma() {
+        let source = DynamicImage::ImageLuma8(GrayImage::from_pixel(400, 300, image::Luma([128])));
+        let mut plotter = Plotter::from_image(4, 3, source, 100, 255).unwrap();
+        let amax = plotter.max_amplitude();
+        let linear = plotter.amplitude(0, 0, amax);
+
+        plotter.set_gamma(2.0);
+        assert!(plotter.amplitude(0, 0, amax) > linear);
+        plotter.set_gamma(0.5);
+        assert!(plotter.amplitude(0, 0, amax) < linear);
+
+        plotter.set_gamma(0.0);
+        assert_eq!(plotter.amplitude(0, 0, amax), 0);
+        plotter.set_gamma(1e6);
+        assert_eq!(plotter.amplitude(0, 0, amax), amax);
+    }
+
+    #[test]
+    fn equalize() {
+        let source = GrayImage::from_fn(400, 300, |x, _| image::Luma([(100 + x * 50 / 399) as u8]));
+        let mut plotter =
+            Plotter::from_image(10, 5, DynamicImage::ImageLuma8(source), 100, 255).unwrap();
+        let range = |plotter: &Plotter| {
+            let values = plotter.source_grid().pixels().map(|pixel| pixel.0[0]);
+            (values.clone().min().unwrap(), values.max().unwrap())
+        };
+        let (low, high) = range(&plotter);
+        assert!(low >= 100 && high <= 150);
+
+        plotter.equalize();
+        assert_eq!(range(&plotter), (0, 255));
+
+        let source = DynamicImage::ImageLuma8(GrayImage::from_pixel(400, 300, image::Luma([128])));
+        let mut plotter = Plotter::from_image(10, 5, source, 100, 255).unwrap();
+        plotter.equalize();
+        assert_eq!(range(&plotter), (128, 128));
+    }
+
+    #[test]
+    fn quantize_levels() {
+        let mut plotter = Plotter::from_image(10, 5, gradient(), 100, 255).unwrap();
+        let amax = plotter.max_amplitude();
+        let distinct = |plotter: &Plotter| {
+            let mut amplitudes: Vec<_> = (0..10)
+                .flat_map(|x| (0..5).map(move |y| (x, y)))
+                .map(|(x, y)| plotter.amplitude(x, y, amax))
+                .collect();
+            amplitudes.sort();
+            amplitudes.dedup();
+            amplitudes
+        };
+        assert!(distinct(&plotter).len() > 2);
+
+        plotter.set_quantize_levels(Some(2));
+        assert_eq!(distinct(&plotter), vec![0, amax]);
+        plotter.set_quantize_levels(Some(0));
+        assert_eq!(distinct(&plotter), vec![0, amax]);
+        plotter.set_quantize_levels(Some(3));
+        assert_eq!(distinct(&plotter).len(), 3);
+    }
+
+    #[test]
+    fn fm_oscillations_fit_cells() {
+        let plotter = Plotter::from_image(10, 5, gradient(), 100, 255).unwrap();
+        let qwave = plotter.quarter_wavelength();
+        for x in 0..10 {
+            let qwaves = plotter.fm_quarter_wavelengths(x, 0);
+            assert_eq!(qwaves.iter().sum::<u32>(), qwave);
+            assert!(qwaves.iter().all(|&q| q >= MIN_FM_QUARTER_WAVELENGTH));
+        }
+        assert!(plotter.fm_quarter_wavelengths(0, 0).len() > 1);
+        assert_eq!(plotter.fm_quarter_wavelengths(9, 0), vec![qwave]);
+    }
+
+    #[test]
+    fn svg() {
+        let plotter = Plotter::from_image(10, 5, gradient(), 100, 255).unwrap();
+        let svg = plotter.to_svg(2.0);
+        let document = roxmltree::Document::parse(&svg).unwrap();
+        let root = document.root_element();
+        assert_eq!(root.tag_name().name(), "svg");
+        assert_eq!(
+            root.attribute("viewBox"),
+            Some(format!("0 0 {} {}", plotter.canvas.iw, plotter.canvas.ih).as_str())
+        );
+        assert_eq!(
+            root.children().filter(|n| n.has_tag_name("path")).count(),
+            5
+        );
+
+        // waves are as tall as when drawn, the first quarter ending on the crest of the first cell
+        let qwave = plotter.quarter_wavelength();
+        let a = plotter.amplitude(0, 0, plotter.effective_max_amplitude(plotter.thickness));
+        let crest = plotter.canvas.ih - 1 - plotter.cell_to_sine_start_y(0) - a;
+        assert!(svg.contains(&format!(", {} {} C", qwave, crest)), "{}", svg);
+    }
+
+    #[test]
+    fn serpentine() {
+        let plotter = Plotter::from_image(10, 5, gradient(), 100, 255).unwrap();
+        let cw = plotter.cell_width() as i32;
+        let path = plotter.path_serpentine();
+        assert_eq!(
+            path.first(),
+            Some(&Point::new(0, plotter.cell_to_sine_start_y(0) as i32))
+        );
+        assert!(
+            path.windows(2)
+                .all(|pair| (pair[0].x - pair[1].x).abs() <= cw
+                    && (pair[0].y - pair[1].y).abs() <= cw)
+        );
+
+        // odd number of rows, so the path ends on the right of the last one
+        let last = path.last().unwrap();
+        assert_eq!(last.y, plotter.cell_to_sine_start_y(4) as i32);
+        assert!(last.x > 9 * cw);
+
+        // waves are as tall as when drawn
+        let crest = path.iter().take_while(|p| p.x < cw).map(|p| p.y).max();
+        let a = plotter.amplitude(0, 0, plotter.effective_max_amplitude(plotter.thickness));
+        assert_eq!(crest, Some((plotter.cell_to_sine_start_y(0) + a) as i32));
+
+        let svg = plotter.to_svg_serpentine(2.0);
+        let document = roxmltree::Document::parse(&svg).unwrap();
+        let polylines: Vec<_> = document
+            .root_element()
+            .children()
+            .filter(|n| n.has_tag_name("polyline"))
+            .collect();
+        assert_eq!(polylines.len(), 1);
+        let points = polylines[0].attribute("points").unwrap();
+        assert_eq!(points.split(' ').count(), path.len());
+    }
+
+    #[test]
+    fn gcode() {
+        let mut plotter = Plotter::from_image(10, 5, gradient(), 100, 255).unwrap();
+        plotter.set_mm_per_pixel(0.5);
+        let path = plotter.path_serpentine();
+        let gcode = plotter.to_gcode(3000.0, 5.0, 0.0);
+        let lines: Vec<_> = gcode.lines().collect();
+
+        let moves: Vec<_> = lines
+            .iter()
+            .filter(|l| l.starts_with("G0") || l.starts_with("G1"))
+            .collect();
+        assert_eq!(moves.first().map(|l| **l), Some("G0 Z5.000"));
+        assert_eq!(lines.last(), Some(&"G0 Z5.000"));
+        assert_eq!(
+            lines.iter().filter(|l| l.starts_with("G1 ")).count(),
+            path.len() - 1
+        );
+
+        // the first point is at the top left, with Y pointing down
+        let y = (plotter.canvas.ih as i32 - 1 - path[0].y) as f64 * 0.5;
+        assert!(lines.contains(&format!("G0 X0.000 Y{:.3}", y).as_str()));
+    }
+
+    #[test]
+    fn source_grid() {
+        let plotter = Plotter::from_image(7, 3, gradient(), 100, 255).unwrap();
+        assert_eq!(plotter.source_grid().dimensions(), (7, 3));
+
+        let grid = plotter.dump_grid();
+        assert_eq!(grid.len(), 3);
+        assert!(grid.iter().all(|row| row.len() == 7));
+        // the gradient goes from black on the left to white on the right
+        assert!(grid[0].windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(grid[1][4], plotter.source_grid().get_pixel(4, 1).0[0]);
+    }
+
+    #[test]
+    fn filter() {
+        let grid = |filter| {
+            let settings = PlotterSettings {
+                nw: 7,
+                nh: 3,
+                filter,
+                ..Default::default()
+            };
+            Plotter::from_settings(gradient(), &settings)
+                .unwrap()
+                .dump_grid()
+        };
+        let triangle = grid(FilterType::Triangle);
+        assert_eq!(
+            triangle,
+            Plotter::from_image(7, 3, gradient(), 100, 255)
+                .unwrap()
+                .dump_grid()
+        );
+        assert_ne!(grid(FilterType::Nearest), triangle);
+    }
+
+    #[test]
+    fn padding() {
+        let mut plotter = Plotter::from_image(10, 5, gradient(), 100, 255).unwrap();
+        let (iw, ih) = (plotter.canvas.iw, plotter.canvas.ih);
+        assert!(plotter.canvas.fw > iw);
+
+        plotter.set_padding_percent(0);
+        assert_eq!((plotter.canvas.fw, plotter.canvas.fh), (iw, ih));
+        assert_eq!((plotter.canvas.ow, plotter.canvas.oh), (0, 0));
+        plotter.render();
+
+        plotter.set_padding_percent(20);
+        assert_eq!((plotter.canvas.iw, plotter.canvas.ih), (iw, ih));
+        assert_eq!(plotter.canvas.fw - iw, min(iw, ih) / 5);
+    }
+
+    #[test]
+    #[cfg(feature = "png")]
+    fn output_size() {
+        let path = save_fixture(gradient(), "sineart_output_size.png");
+        for target in [1, 300, 1000, 1234] {
+            let plotter = Plotter::with_output_size(10, 5, &path, target, 255).unwrap();
+            let iw = plotter.canvas.iw;
+            assert!(
+                iw.abs_diff(target) <= 40 || target < 40,
+                "{} {}",
+                target,
+                iw
+            );
+            assert_eq!(plotter.cell_width(), 4 * plotter.quarter_wavelength());
+            assert_eq!(plotter.canvas.ih, 300 * iw / 400);
+        }
+        assert_eq!(
+            Plotter::with_output_size(10, 5, &path, 1001, 255)
+                .unwrap()
+                .canvas
+                .iw,
+            1001
+        );
+        assert!(matches!(
+            Plotter::with_output_size(10, 5, &path, 0, 255),
+            Err(PlotterError::InvalidDimensions(_))
+        ));
+        // rounding to whole waves per cell overflows the width
+        assert!(matches!(
+            Plotter::with_output_size(1, 1, &path, u32::MAX, 255),
+            Err(PlotterError::InvalidDimensions(_))
+        ));
+        assert!(matches!(
+            Plotter::with_output_size(1 << 30, 1, &path, 1000, 255),
+            Err(PlotterError::InvalidDimensions(_))
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "png")]
+    fn builder() {
+        let path = save_fixture(gradient(), "sineart_builder.png");
+        let mut built = PlotterBuilder::new()
+            .source(&path)
+            .cells(10, 5)
+            .scale(50)
+            .thickness(2)
+            .invert(true)
+            .build()
+            .unwrap();
+        built.render();
+
+        let mut plotter = Plotter::new(10, 5, &path, 50, 200).unwrap();
+        plotter.set_invert(true);
+        plotter.draw(2);
+
+        assert_eq!(built.canvas, plotter.canvas);
+        assert!(matches!(
+            PlotterBuilder::new().build(),
+            Err(PlotterError::MissingSource)
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn settings_toml() {
+        let settings = PlotterSettings {
+            nw: 10,
+            nh: 5,
+            scale: 50,
+            thickness: 2,
+            filter: FilterType::Lanczos3,
+            mapping: Mapping::Gamma(2.2),
+            shape: StrokeShape::Triangle,
+            wavelength_px: Some(12),
+            jitter: 1.5,
+            seed: 42,
+            ..Default::default()
+        };
+        let text = toml::to_string(&settings).unwrap();
+        assert!(text.contains("filter = \"lanczos3\""), "{}", text);
+        let loaded: PlotterSettings = toml::from_str(&text).unwrap();
+        assert_eq!(loaded, settings);
+
+        // missing fields take their default
+        let partial: PlotterSettings = toml::from_str("nw = 10\nshape = \"square\"").unwrap();
+        assert_eq!(partial.nw, 10);
+        assert_eq!(partial.shape, StrokeShape::Square);
+        assert_eq!(partial.nh, PlotterSettings::default().nh);
+        assert!(toml::from_str::<PlotterSettings>("filter = \"blurry\"").is_err());
+
+        // the same plotter as configured through the builder
+        let path = save_fixture(gradient(), "sineart_settings_toml.png");
+        let mut loaded = Plotter::from_settings(gradient(), &loaded).unwrap();
+        loaded.render();
+        let mut built = PlotterBuilder::new()
+            .source(&path)
+            .settings(settings)
+            .build()
+            .unwrap();
+        built.render();
+        assert_eq!(loaded.canvas, built.canvas);
+        assert_eq!(loaded.shape, StrokeShape::Triangle);
+    }
+
+    #[test]
+    fn optimize_travel() {
+        let segment = |x: i32, y: i32| vec![Point::new(x, y), Point::new(x + 10, y)];
+        // short strokes on either side of the canvas, alternating, one of them backwards
+        let strokes = vec![
+            segment(0, 0),
+            segment(200, 0),
+            segment(0, 20),
+            segment(200, 20),
+            segment(0, 40).into_iter().rev().collect(),
+            vec![],
+        ];
+        let before = pen_up_travel(&strokes);
+        let optimized = super::optimize_travel(strokes.clone());
+        let after = pen_up_travel(&optimized);
+        assert!(after < before, "{} >= {}", after, before);
+        assert_eq!(optimized[0], strokes[0]);
+        assert_eq!(optimized.len(), 5);
+
+        // every stroke is still there, possibly reversed
+        for stroke in strokes.iter().filter(|s| !s.is_empty()) {
+            let reversed: Vec<_> = stroke.iter().rev().copied().collect();
+            assert!(optimized.contains(stroke) || optimized.contains(&reversed));
+        }
+        // the left column comes first, every stroke entered from its nearest end
+        assert_eq!(
+            optimized[1],
+            segment(0, 20).into_iter().rev().collect::<Vec<_>>()
+        );
+        assert_eq!(optimized[2], segment(0, 40));
+
+        assert!(super::optimize_travel(vec![]).is_empty());
+        assert_eq!(pen_up_travel(&[]), 0.0);
+    }
+
+    #[test]
+    fn from_grid() {
+        // dark on the diagonal, lighter further away
+        let grid = GrayImage::from_fn(4, 4, |x, y| {
+            image::Luma([(x as i32 - y as i32).unsigned_abs() as u8 * 80])
+        });
+        let mut plotter = Plotter::from_grid(grid.clone(), 30).unwrap();
+        assert_eq!(plotter.source, grid);
+        // the grid is kept once, standing for its own colors and original
+        assert!(plotter.colors.is_none() && plotter.original.is_none());
+        assert_eq!(plotter.color(1, 0), Rgb([80; 3]));
+        assert_eq!(plotter.cell_width(), 32);
+        assert_eq!(plotter.cell_height(), 32);
+
+        let stats = plotter.draw_with_stats(1);
+        assert_eq!(stats.waves, 16);
+        assert_eq!(stats.max_amplitude, plotter.effective_max_amplitude(1));
+        assert_eq!(plotter.amplitude(3, 0, 100), plotter.amplitude(0, 3, 100));
+        assert!(plotter.amplitude(0, 0, 100) > plotter.amplitude(1, 0, 100));
+
+        plotter.equalize();
+        assert_ne!(plotter.source, grid);
+        assert_eq!(plotter.original(), &grid);
+
+        assert!(Plotter::from_grid(GrayImage::new(0, 4), 30).is_err());
+        assert!(Plotter::from_grid(GrayImage::new(4, 4), u32::MAX).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "png")]
+    fn from_image() {
+        let path = save_fixture(gradient(), "sineart_from_image.png");
+        let mut decoded = Plotter::from_image(10, 5, gradient(), 50, 255).unwrap();
+        decoded.draw(2);
+
+        let mut plotter = Plotter::new(10, 5, &path, 50, 255).unwrap();
+        plotter.draw(2);
+
+        assert_eq!(decoded.canvas, plotter.canvas);
+    }
+
+    #[test]
+    fn missing_source() {
+        let result = Plotter::new(10, 10, "does/not/exist.jpg", 100, 255);
+        assert!(matches!(result, Err(PlotterError::Io(_))));
+    }
+
+    #[test]
+    #[cfg(feature = "png")]
+    fn truncated_source() {
+        let path = std::env::temp_dir().join("sineart_truncated.png");
+        let mut bytes = Vec::new();
+        GrayImage::new(32, 32)
+            .write_to(
+                &mut io::Cursor::new(&mut bytes),
+                image::ImageOutputFormat::Png,
+            )
+            .unwrap();
+        std::fs::write(&path, &bytes[..bytes.len() / 2]).unwrap();
+
+        let result = Plotter::new(10, 10, &path, 100, 255);
+        assert!(matches!(result, Err(PlotterError::Decode(_))));
+    }
+
+    #[test]
+    fn zero_cells() {
+        let result = Plotter::from_image(0, 10, DynamicImage::new_luma8(10, 10), 100, 255);
+        assert!(matches!(result, Err(PlotterError::InvalidDimensions(_))));
+    }
+
+    #[test]
+    fn tiny_inputs() {
+        let pixel = || DynamicImage::new_luma8(1, 1);
+        let result = Plotter::from_image(4, 3, pixel(), 100, 255);
+        assert!(matches!(result, Err(PlotterError::InvalidDimensions(_))));
+        let result = Plotter::from_image(1, 1, pixel(), 0, 255);
+        assert!(matches!(result, Err(PlotterError::InvalidDimensions(_))));
+        assert!(Plotter::from_image(1, 1, pixel(), 100, 255).is_ok());
+
+        // a wide strip leaves no room for the rows
+        let strip = DynamicImage::new_luma8(400, 10);
+        let result = Plotter::from_image(10, 10, strip, 100, 255);
+        assert!(matches!(result, Err(PlotterError::InvalidDimensions(_))));
+    }
+
+    #[test]
+    fn tall_source_overflow() {
+        // the width fits a u32 but the height following the aspect ratio does not
+        let column = DynamicImage::new_luma8(1, 100_000);
+        let result = Plotter::from_image(1, 1, column, 5_000_000, 255);
+        assert!(matches!(result, Err(PlotterError::InvalidDimensions(_))));
     }
 }
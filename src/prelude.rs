@@ -0,0 +1,21 @@
+//! Everything needed for common use in one import.
+//!
+//! ```
+//! use sineart::prelude::*;
+//!
+//! let source = image::DynamicImage::new_luma8(400, 300);
+//! let mut plotter = Plotter::from_image(10, 5, source, 100, 200).unwrap();
+//! plotter.draw(2);
+//! SineWave::new(Point::new(0, 10), 5, 4, 3).draw(&mut plotter.canvas);
+//! assert!(plotter.canvas.get_xy(0, 10) < 255);
+//! ```
+
+pub use crate::{
+    canvas::{Canvas, XYDrawable},
+    curves::{
+        antialiased_value,
+        sine::{Sine, SineWave},
+        Curve, Drawable, Gray, Point,
+    },
+    plotter::{Plotter, PlotterBuilder, PlotterSettings},
+};
@@ -0,0 +1,78 @@
+use std::process::{Command, Output};
+
+/// Write a small horizontal gradient to a temporary directory and return its path.
+#[cfg(feature = "png")]
+fn fixture(name: &str) -> std::path::PathBuf {
+    use image::{GrayImage, Luma};
+    let dir = std::env::temp_dir().join("sineart_cli");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(name);
+    GrayImage::from_fn(320, 240, |x, _| Luma([(x * 255 / 319) as u8]))
+        .save(&path)
+        .unwrap();
+    path
+}
+
+fn sineart(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_sineart"))
+        .args(args)
+        .output()
+        .expect("could not run sineart")
+}
+
+#[test]
+#[cfg(feature = "png")]
+fn renders_fixture() {
+    let source = fixture("renders_fixture.png");
+    let output = source.with_file_name("renders_fixture_out.png");
+    let _ = std::fs::remove_file(&output);
+
+    let result = sineart(&[
+        "--source",
+        source.to_str().unwrap(),
+        "--width",
+        "8",
+        "--height",
+        "6",
+        "--output",
+        output.to_str().unwrap(),
+    ]);
+
+    assert!(result.status.success(), "{:?}", result);
+    assert!(image::open(&output).is_ok());
+}
+
+#[test]
+fn missing_source() {
+    let result = sineart(&["--source", "does/not/exist.jpg"]);
+    assert!(!result.status.success());
+    assert!(String::from_utf8_lossy(&result.stderr).contains("does not exist"));
+}
+
+#[test]
+#[cfg(feature = "png")]
+fn zero_width() {
+    let source = fixture("zero_width.png");
+    let result = sineart(&["--source", source.to_str().unwrap(), "--width", "0"]);
+    assert!(!result.status.success());
+    assert!(String::from_utf8_lossy(&result.stderr).contains("non-zero"));
+}
+
+#[test]
+#[cfg(feature = "png")]
+fn unwritable_output() {
+    let source = fixture("unwritable_output.png");
+    let output = source.with_file_name("missing_dir/out.png");
+    let result = sineart(&[
+        "--source",
+        source.to_str().unwrap(),
+        "--width",
+        "8",
+        "--height",
+        "6",
+        "--output",
+        output.to_str().unwrap(),
+    ]);
+    assert!(!result.status.success());
+    assert!(String::from_utf8_lossy(&result.stderr).contains("could not save"));
+}
@@ -0,0 +1,52 @@
+//! Renders compared pixel for pixel with committed golden images, to catch rendering regressions.
+//! After an intended change in the output, regenerate the goldens with
+//! `SINEART_BLESS=1 cargo test --test golden` and check the new images by eye before committing.
+//! Goldens are PNG files, so the whole suite needs the `png` feature.
+#![cfg(feature = "png")]
+use image::{DynamicImage, GrayImage, Luma};
+use sineart::Plotter;
+use std::path::Path;
+
+/// Radial gradient, dark in the center and white in the corners, with every level in between.
+fn fixture() -> DynamicImage {
+    let source = GrayImage::from_fn(200, 150, |x, y| {
+        let d = (x as f64 - 100.0).hypot(y as f64 - 75.0) / 125.0;
+        Luma([(255.0 * d.min(1.0)) as u8])
+    });
+    DynamicImage::ImageLuma8(source)
+}
+
+/// Compare an image with its golden, or overwrite the golden when blessing.
+fn check(image: &GrayImage, name: &str) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(name);
+    if std::env::var_os("SINEART_BLESS").is_some() {
+        image.save(&path).unwrap();
+        return;
+    }
+    let golden = image::open(&path)
+        .unwrap_or_else(|e| panic!("could not open {}: {}", path.display(), e))
+        .into_luma8();
+    assert_eq!(image.dimensions(), golden.dimensions(), "{}", name);
+    let differences = image
+        .pixels()
+        .zip(golden.pixels())
+        .filter(|(a, b)| a != b)
+        .count();
+    assert_eq!(differences, 0, "{} differs from its golden", name);
+}
+
+#[test]
+fn sine() {
+    let mut plotter = Plotter::from_image(12, 9, fixture(), 100, 255).unwrap();
+    plotter.draw(1);
+    check(plotter.canvas.image(), "sine.png");
+}
+
+#[test]
+fn supersampled() {
+    let mut plotter = Plotter::from_image(12, 9, fixture(), 100, 255).unwrap();
+    plotter.draw_supersampled(1, 3);
+    check(plotter.canvas.image(), "supersampled.png");
+}
@@ -0,0 +1,89 @@
+use sineart::{
+    antialiased_value,
+    canvas::{Canvas, XYDrawable},
+    Curve, Drawable, Point, Sine, SineWave,
+};
+
+/// Quarter circle of radius 30 around (10, 10), defined outside the crate.
+struct Arc {
+    start: Point,
+    stop: Point,
+}
+
+impl Curve for Arc {
+    type T = i64;
+
+    fn start(&self) -> &Point {
+        &self.start
+    }
+
+    fn stop(&self) -> &Point {
+        &self.stop
+    }
+
+    fn equation(&self, point: &Point) -> i64 {
+        let (dx, dy) = ((point.x - 10) as i64, (point.y - 10) as i64);
+        dx * dx + dy * dy - 900
+    }
+}
+
+#[test]
+fn draw_sine_wave() {
+    let mut canvas = Canvas::new([120, 220], [100, 200]);
+    SineWave::new(Point::new(0, 50), 30, 10, 4).draw_thick(&mut canvas, 1);
+
+    // crests of all four periods are drawn, and nothing past the end of the last one
+    for period in 0..4 {
+        assert_eq!(canvas.get_xy(40 * period + 10, 80), 0);
+    }
+    assert!((170..200).all(|x| (0..100).all(|y| canvas.get_xy(x, y) == 255)));
+}
+
+#[test]
+fn draw_sine() {
+    let mut canvas = Canvas::new([120, 220], [100, 200]);
+    Sine::new(Point::new(0, 50), 30, 10).draw(&mut canvas);
+    assert_eq!(canvas.get_xy(30, 20), 0);
+}
+
+#[test]
+fn draw_custom_curve() {
+    let arc = Arc {
+        start: Point::new(40, 10),
+        stop: Point::new(10, 40),
+    };
+    let mut canvas = Canvas::new([70; 2], [60; 2]);
+    arc.draw(&mut canvas);
+    assert_eq!(canvas.get_xy(40, 10), 0);
+    assert_eq!(canvas.get_xy(10, 40), 0);
+    assert_eq!(canvas.get_xy(31, 31), 0);
+    assert_eq!(canvas.get_xy(10, 10), 255);
+
+    let mut antialiased = Canvas::new([70; 2], [60; 2]);
+    arc.draw_antialiased(&mut antialiased);
+    assert_eq!(antialiased.get_xy(40, 10), antialiased_value::<u8>(0.0));
+    assert!(antialiased
+        .image()
+        .pixels()
+        .any(|p| p.0[0] > 0 && p.0[0] < 255));
+}
+
+#[test]
+#[cfg(feature = "webp")]
+fn decode_webp() {
+    // lossless 32x24 horizontal gradient, black on the left
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/gradient.webp");
+    let mut plotter = sineart::Plotter::new(8, 6, path, 1000, 255).unwrap();
+    plotter.draw(1);
+
+    // waves are taller in the dark cells on the left than in the light ones on the right
+    let canvas = &plotter.canvas;
+    let cw = plotter.cell_width() as i32;
+    let height = |cell: i32| {
+        (0..canvas.ih as i32)
+            .filter(|&y| (cell * cw..(cell + 1) * cw).any(|x| canvas.get_xy(x, y) == 0))
+            .count()
+    };
+    assert!(height(0) > height(7));
+    assert!(height(1) >= height(6));
+}